@@ -1,30 +1,35 @@
 #![allow(dead_code)]
 
-use std::ops::Mul;
 // use std::ops::Mul;
 use crate::matrix4x3::*;
 use crate::vector3::*;
 
 // Implement a 3D axially aligned bounding box
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AABB3 {
     pub min: Vector3,
     pub max: Vector3,
 }
 
 impl AABB3 {
+    // Create a new, empty box.  is_empty() is true until something is
+    // added, so it is ready to have points accumulated into it with
+    // add_vector3()
     pub fn new() -> Self {
-        AABB3 {
-            min: Vector3::identity(),
-            max: Vector3::identity(),
-        }
+        let mut b = AABB3 {
+            min: Vector3::zero(),
+            max: Vector3::zero(),
+        };
+        b.empty();
+        b
     }
 
     // Query for dimensions
 
     pub fn size(&self) -> Vector3 {
-        &self.max - &self.min
+        self.max - self.min
     }
 
     pub fn x_size(&self) -> f32 {
@@ -40,7 +45,39 @@ impl AABB3 {
     }
 
     pub fn center(&self) -> Vector3 {
-        (&(&self.min + &self.max)).mul(0.5)
+        (self.min + self.max) * 0.5
+    }
+
+    // Half the size on each axis, the distance from the center to the
+    // faces
+    pub fn extents(&self) -> Vector3 {
+        self.size() * 0.5
+    }
+
+    // Volume, surface area, and length of the diagonal.  These are handy
+    // for spatial heuristics, such as choosing where to split a bounding
+    // volume hierarchy.  An empty box returns 0 for all of them
+
+    pub fn volume(&self) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.x_size() * self.y_size() * self.z_size()
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let (x, y, z) = (self.x_size(), self.y_size(), self.z_size());
+        2.0 * (x * y + y * z + z * x)
+    }
+
+    pub fn diagonal_length(&self) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.size().magnitude()
     }
 
     //---------------------------------------------------------------------------
@@ -93,6 +130,11 @@ impl AABB3 {
         }
     }
 
+    // Return all 8 corner points, numbered as in corner()
+    pub fn corners(&self) -> [Vector3; 8] {
+        std::array::from_fn(|i| self.corner(i as i32))
+    }
+
     // "Empty" the box, by setting the values to really
     // large/small numbers
     pub fn empty(&mut self) {
@@ -115,16 +157,16 @@ impl AABB3 {
         if p.x > self.max.x {
             self.max.x = p.x
         };
-        if p.y < self.min.x {
+        if p.y < self.min.y {
             self.min.y = p.y
         };
-        if p.y > self.max.x {
+        if p.y > self.max.y {
             self.max.y = p.y
         };
-        if p.z < self.min.x {
+        if p.z < self.min.z {
             self.min.z = p.z
         };
-        if p.z > self.max.x {
+        if p.z > self.max.z {
             self.max.z = p.z
         };
     }
@@ -136,23 +178,72 @@ impl AABB3 {
         if box_aabb3.min.x < self.min.x {
             self.min.x = box_aabb3.min.x
         };
-        if box_aabb3.min.x > self.max.x {
-            self.max.x = box_aabb3.min.x
+        if box_aabb3.max.x > self.max.x {
+            self.max.x = box_aabb3.max.x
         };
-        if box_aabb3.min.y < self.min.x {
+        if box_aabb3.min.y < self.min.y {
             self.min.y = box_aabb3.min.y
         };
-        if box_aabb3.min.y > self.max.x {
-            self.max.y = box_aabb3.min.y
+        if box_aabb3.max.y > self.max.y {
+            self.max.y = box_aabb3.max.y
         };
-        if box_aabb3.min.z < self.min.x {
+        if box_aabb3.min.z < self.min.z {
             self.min.z = box_aabb3.min.z
         };
-        if box_aabb3.min.z > self.max.x {
-            self.max.z = box_aabb3.min.z
+        if box_aabb3.max.z > self.max.z {
+            self.max.z = box_aabb3.max.z
         };
     }
 
+    // Grow the box by margin in every direction, such as for a collision
+    // "skin."  A negative margin shrinks the box, and if it shrinks by more
+    // than half its size on any axis, the result is empty
+    pub fn inflate(&mut self, margin: f32) {
+        let m = Vector3::new(margin, margin, margin);
+        self.min -= m;
+        self.max += m;
+    }
+
+    // Return a copy of the box grown by margin, leaving this one unchanged.
+    // See inflate()
+    pub fn expanded(&self, margin: f32) -> AABB3 {
+        let mut b = *self;
+        b.inflate(margin);
+        b
+    }
+
+    //---------------------------------------------------------------------------
+    // from_points
+    //
+    // Build the box containing a list of points.  An empty list gives an
+    // empty box
+    pub fn from_points(points: &[Vector3]) -> AABB3 {
+        let mut b = AABB3::new();
+        for p in points {
+            b.add_vector3(p);
+        }
+        b
+    }
+
+    //---------------------------------------------------------------------------
+    // merge
+    //
+    // Return the smallest box containing both boxes.  An empty box adds
+    // nothing, even if it is "inverted" by some other amount than empty()
+    // produces, such as after shrinking with inflate()
+    pub fn merge(a: &AABB3, b: &AABB3) -> AABB3 {
+        if a.is_empty() {
+            return *b;
+        }
+        if b.is_empty() {
+            return *a;
+        }
+
+        let mut result = *a;
+        result.add_aabb(b);
+        result
+    }
+
     //---------------------------------------------------------------------------
     // set_to_transformed_box
     // Transform the box and compute the new AABB.  Remember, this always
@@ -247,6 +338,18 @@ impl AABB3 {
         }
     }
 
+    //---------------------------------------------------------------------------
+    // transform
+    //
+    // Return the AABB of this box after transformation by m.  Same as
+    // set_to_transformed_box(), but returns the result instead of
+    // overwriting a box
+    pub fn transform(&self, m: &Matrix4x3) -> AABB3 {
+        let mut result = AABB3::new();
+        result.set_to_transformed_box(self, m);
+        result
+    }
+
     // Return true if the box is empty
     pub fn is_empty(&self) -> bool {
         // Check if we're inverted on any axis
@@ -267,38 +370,18 @@ impl AABB3 {
 
     // Return the closest point on this box to another point
     pub fn closest_point_to(&self, p: &Vector3) -> Vector3 {
-        let mut r: Vector3 = Vector3 {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        };
-
         // "Push" p into the box, on each dimension
-        if p.x < self.min.x {
-            r.x = self.min.x;
-        } else if p.x > self.max.x {
-            r.x = self.max.x;
-        } else {
-            r.x = p.x;
-        }
-
-        if p.y < self.min.y {
-            r.y = self.min.y;
-        } else if p.y > self.max.y {
-            r.y = self.max.y;
-        } else {
-            r.y = p.y;
-        }
+        p.clamp(&self.min, &self.max)
+    }
 
-        if p.z < self.min.z {
-            r.z = self.min.z;
-        } else if p.z > self.max.z {
-            r.z = self.max.z;
-        } else {
-            r.z = p.z;
+    // Return a sphere (center, radius) that contains the box.  The sphere
+    // passes through the corners, so the radius is half the diagonal.  An
+    // empty box gives a zero radius at the origin
+    pub fn bounding_sphere(&self) -> (Vector3, f32) {
+        if self.is_empty() {
+            return (Vector3::zero(), 0.0);
         }
-
-        r
+        (self.center(), self.diagonal_length() * 0.5)
     }
 
     // Return true if we intersect a sphere.  Uses Arvo's algorithm.
@@ -309,7 +392,7 @@ impl AABB3 {
 
         // Check if it's within range
 
-        distance_squared(center, &closest_point) < radius * radius
+        center.distance_squared_to(&closest_point) < radius * radius
     }
 
     // ray_intersect
@@ -666,6 +749,34 @@ impl AABB3 {
         true
     }
 
+    //---------------------------------------------------------------------------
+    // overlaps
+    //
+    // Return true if the two boxes overlap.  Boxes that only touch, sharing
+    // part of a face, edge, or corner, count as overlapping, the same as in
+    // intersect_aabbs()
+    pub fn overlaps(&self, other: &AABB3) -> bool {
+        AABB3::intersect_aabbs(self, other, None)
+    }
+
+    //---------------------------------------------------------------------------
+    // contains_aabb
+    //
+    // Return true if the other box is completely inside this one.  The
+    // faces may touch.  An empty box is contained by any box
+    pub fn contains_aabb(&self, other: &AABB3) -> bool {
+        if other.is_empty() {
+            return true;
+        }
+
+        (other.min.x >= self.min.x)
+            && (other.max.x <= self.max.x)
+            && (other.min.y >= self.min.y)
+            && (other.max.y <= self.max.y)
+            && (other.min.z >= self.min.z)
+            && (other.max.z <= self.max.z)
+    }
+
     //---------------------------------------------------------------------------
     // intersect_moving_aabb
     //
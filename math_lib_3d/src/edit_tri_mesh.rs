@@ -4,11 +4,13 @@
 
 use crate::aabb3::AABB3;
 use crate::matrix4x3::Matrix4x3;
-use crate::vector3::{cross_product, Vector3};
+use crate::utils::safe_acos;
+use crate::vector3::{Vec2, Vector3};
 use debug_print::debug_println;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
-#[derive(Clone, Debug)]
+#[derive(PartialEq, Debug)]
 pub struct EditTriMesh {
     // The mesh lists
     // vAlloc: f32,
@@ -26,7 +28,7 @@ pub struct EditTriMesh {
     pub pList: Vec<Part>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Vertex {
     // 3D vertex position;
     pub p: Vector3,
@@ -48,7 +50,7 @@ pub struct Vertex {
     pub mark: i32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Vert {
     pub index: usize,
     // index into the vertex list
@@ -57,7 +59,7 @@ pub struct Vert {
     pub v: f32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Tri {
     // Face vertices.
     pub v: [Vert; 3],
@@ -75,14 +77,14 @@ pub struct Tri {
     pub mark: i32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Material {
     pub diffuseTextureName: String,
     // Utility "mark" variable, often handy
     pub mark: i32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Part {
     pub name: String,
     // Utility "mark" variable, often handy
@@ -105,6 +107,14 @@ pub struct OptimizationParameters {
     pub cosOfEdgeAngleTolerance: f32,
 }
 
+// How face normals are weighted when they are summed into the vertex
+// normals.  See compute_vertex_normals_weighted
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NormalWeighting {
+    Area,  // weight by triangle area
+    Angle, // weight by the angle of the triangle's corner at the vertex
+}
+
 impl Vertex {
     //---------------------------------------------------------------------------
     // Vertex::setDefaults
@@ -120,6 +130,16 @@ impl Vertex {
             mark: 0,
         }
     }
+
+    // Texture mapping coordinates as a 2D vector
+    pub fn uv(&self) -> Vec2 {
+        Vec2::new(self.u, self.v)
+    }
+
+    pub fn set_uv(&mut self, uv: &Vec2) {
+        self.u = uv.x;
+        self.v = uv.y;
+    }
 }
 
 impl Vert {
@@ -130,6 +150,16 @@ impl Vert {
             v: 0.0,
         }
     }
+
+    // Texture mapping coordinates as a 2D vector
+    pub fn uv(&self) -> Vec2 {
+        Vec2::new(self.u, self.v)
+    }
+
+    pub fn set_uv(&mut self, uv: &Vec2) {
+        self.u = uv.x;
+        self.v = uv.y;
+    }
 }
 
 impl Tri {
@@ -745,7 +775,7 @@ impl EditTriMesh {
 
     pub fn deleteMaterial(&mut self, materialIndex: usize) {
         // Check index.  Warn in debug build, don't crash release
-        if materialIndex >= self.vList.len() {
+        if materialIndex >= self.mList.len() {
             debug_assert!(
                 false,
                 "{}",
@@ -779,7 +809,7 @@ impl EditTriMesh {
 
     pub fn deletePart(&mut self, partIndex: usize) {
         // Check index.  Warn in debug build, don't crash release
-        if partIndex >= self.vList.len() {
+        if partIndex >= self.pList.len() {
             debug_assert!(
                 false,
                 "{}",
@@ -794,8 +824,8 @@ impl EditTriMesh {
                 tri.mark = 1;
             } else {
                 tri.mark = 0;
-                if tri.material > partIndex {
-                    tri.material -= 1;
+                if tri.part > partIndex {
+                    tri.part -= 1;
                 }
             }
         }
@@ -1031,8 +1061,8 @@ impl EditTriMesh {
                 let old_v: &Vertex = &self.vList[s_index];
 
                 // Copy the vertex
-                new_v.p.copy(&old_v.p);
-                new_v.normal.copy(&old_v.normal);
+                new_v.p = old_v.p;
+                new_v.normal = old_v.normal;
                 new_v.u = old_v.u;
                 new_v.v = old_v.v;
 
@@ -1055,6 +1085,24 @@ impl EditTriMesh {
         }
     }
 
+    //---------------------------------------------------------------------------
+    // flip_winding
+    //
+    // Reverse the vertex order of every triangle, turning the mesh inside
+    // out.  Useful for imported meshes with inward facing normals, which
+    // would otherwise be culled by the renderer's backface mode.  The
+    // stored triangle and vertex normals are negated to match, so there's
+    // no need to recompute them
+    pub fn flip_winding(&mut self) {
+        for tri in self.tList.iter_mut() {
+            tri.v.swap(1, 2);
+            tri.normal = -tri.normal;
+        }
+        for vertex in self.vList.iter_mut() {
+            vertex.normal = -vertex.normal;
+        }
+    }
+
     //---------------------------------------------------------------------------
     // extractParts
     //
@@ -1092,7 +1140,7 @@ impl EditTriMesh {
 
             for tri in self.tList.iter_mut() {
                 if tri.part != partIndex {
-                    return;
+                    continue;
                 }
 
                 let mut new_tri = tri.clone();
@@ -1194,11 +1242,11 @@ impl EditTriMesh {
 
         // Compute clockwise edge vectors.  We use the edge vector
         // indexing that agrees with Section 12.6.
-        let e1 = &*v3 - &*v2;
-        let e2 = &*v1 - &*v3;
+        let e1 = v3 - v2;
+        let e2 = v1 - v3;
 
         // Cross product to compute surface normal
-        t.normal = cross_product(&e1, &e2);
+        t.normal = e1.cross(&e2);
 
         // Normalize it
         t.normal.normalize();
@@ -1212,11 +1260,11 @@ impl EditTriMesh {
 
         // Compute clockwise edge vectors.  We use the edge vector
         // indexing that agrees with Section 12.6.
-        let e1 = &*v3 - &*v2;
-        let e2 = &*v1 - &*v3;
+        let e1 = v3 - v2;
+        let e2 = v1 - v3;
 
         // Cross product to compute surface normal
-        t.normal = cross_product(&e1, &e2);
+        t.normal = e1.cross(&e2);
 
         // Normalize it
         t.normal.normalize();
@@ -1264,6 +1312,58 @@ impl EditTriMesh {
         }
     }
 
+    //---------------------------------------------------------------------------
+    // compute_vertex_normals_weighted
+    //
+    // Compute vertex level surface normals, like computeVertexNormals, but
+    // weight each triangle normal before summing it in.  Plain summing lets
+    // a few small triangles crowded around a vertex outvote one large one,
+    // which shades poorly on irregular meshes.  Area weighting fixes that.
+    // Angle weighting also makes the result independent of how a surface
+    // is split into triangles.  This automatically computes the triangle
+    // level surface normals
+    pub fn compute_vertex_normals_weighted(&mut self, weighting: NormalWeighting) {
+        // First, make sure triangle level surface normals are up-to-date
+        self.computeTriNormals();
+
+        // Zero out vertex normals
+        for vertex in self.vList.iter_mut() {
+            vertex.normal.set_to_zero();
+        }
+
+        // Sum in the weighted triangle normals
+        for tri in self.tList.iter() {
+            let p = [
+                self.vList[tri.v[0].index].p,
+                self.vList[tri.v[1].index].p,
+                self.vList[tri.v[2].index].p,
+            ];
+
+            // Same clockwise edge vectors as computeOneTriNormal.  The
+            // cross product is twice the area
+            let e1 = p[2] - p[1];
+            let e2 = p[0] - p[2];
+            let area = e1.cross(&e2).magnitude() * 0.5;
+
+            for j in 0..3 {
+                let weight = match weighting {
+                    NormalWeighting::Area => area,
+                    NormalWeighting::Angle => {
+                        let e1 = (p[(j + 1) % 3] - p[j]).normalized();
+                        let e2 = (p[(j + 2) % 3] - p[j]).normalized();
+                        safe_acos(e1.dot(&e2))
+                    }
+                };
+                self.vList[tri.v[j].index].normal += tri.normal * weight;
+            }
+        }
+
+        // Now "average" the vertex surface normals, by normalizing them
+        for vertex in self.vList.iter_mut() {
+            vertex.normal.normalize();
+        }
+    }
+
     //---------------------------------------------------------------------------
     // computeBounds
     //
@@ -1272,7 +1372,6 @@ impl EditTriMesh {
     pub fn computeBounds(&mut self) -> AABB3 {
         // Generate the bounding box of the vertices
         let mut bounding_box = AABB3::new();
-        bounding_box.empty();
 
         for vertex in self.vList.iter_mut() {
             bounding_box.add_vector3(&vertex.p);
@@ -1379,6 +1478,92 @@ impl EditTriMesh {
         todo!()
     }
 
+    //---------------------------------------------------------------------------
+    // remove_duplicate_vertices
+    //
+    // Merge vertices whose positions are within tolerance of each other,
+    // ignoring UVs and normals.  This is a quicker cleanup than
+    // weldVertices, for import pipelines that recompute the normals and
+    // UVs anyway.  Each vertex is merged into the first vertex in the list
+    // that is close enough.  Triangles that collapse as a result are
+    // deleted, and so are vertices that are no longer used.  The vertex
+    // list is left in the order used by optimizeVertexOrder
+    pub fn remove_duplicate_vertices(&mut self, tolerance: f32) {
+        let tolerance_sq = tolerance * tolerance;
+
+        // Put the surviving vertices into a grid with cells the size of
+        // the tolerance, so we only have to check the neighboring cells.
+        // With no tolerance, the cells are individual positions
+        let cell_of = |p: &Vector3| -> [i64; 3] {
+            if tolerance > 0.0 {
+                [
+                    (p.x / tolerance).floor() as i64,
+                    (p.y / tolerance).floor() as i64,
+                    (p.z / tolerance).floor() as i64,
+                ]
+            } else {
+                [
+                    (p.x + 0.0).to_bits() as i64,
+                    (p.y + 0.0).to_bits() as i64,
+                    (p.z + 0.0).to_bits() as i64,
+                ]
+            }
+        };
+        let reach: i64 = if tolerance > 0.0 { 1 } else { 0 };
+
+        let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        let mut remap: Vec<usize> = Vec::with_capacity(self.vList.len());
+
+        for (vIndex, vertex) in self.vList.iter().enumerate() {
+            let cell = cell_of(&vertex.p);
+
+            // Search the neighboring cells for an earlier vertex that's
+            // close enough
+            let mut found: Option<usize> = None;
+            for dx in -reach..=reach {
+                for dy in -reach..=reach {
+                    for dz in -reach..=reach {
+                        let key = [
+                            cell[0].wrapping_add(dx),
+                            cell[1].wrapping_add(dy),
+                            cell[2].wrapping_add(dz),
+                        ];
+                        if let Some(list) = grid.get(&key) {
+                            for &other in list.iter() {
+                                if self.vList[other].p.distance_squared_to(&vertex.p)
+                                    <= tolerance_sq
+                                    && found.is_none_or(|f| other < f)
+                                {
+                                    found = Some(other);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            match found {
+                Some(other) => remap.push(other),
+                None => {
+                    grid.entry(cell).or_default().push(vIndex);
+                    remap.push(vIndex);
+                }
+            }
+        }
+
+        // Point the triangles at the surviving vertices
+        for tri in self.tList.iter_mut() {
+            for j in 0..3 {
+                tri.v[j].index = remap[tri.v[j].index];
+            }
+        }
+
+        // Get rid of any triangles that collapsed, and then the vertices
+        // that nothing uses anymore
+        self.deleteDegenerateTris();
+        self.optimizeVertexOrder(true);
+    }
+
     //---------------------------------------------------------------------------
     // copyUvsIntoVertices
     //
@@ -1388,6 +1573,14 @@ impl EditTriMesh {
         // Mark all vertices indicating that their UV's are invalid
         self.markAllVertices(0);
 
+        // Bucket the vertices by quantized position and normal, so we can
+        // find geometrically identical vertices without a linear search.
+        // Each bucket is kept in vertex list order
+        let mut buckets: HashMap<[i32; 6], Vec<usize>> = HashMap::new();
+        for (vIndex, vertex) in self.vList.iter().enumerate() {
+            buckets.entry(geometryKey(vertex)).or_default().push(vIndex);
+        }
+
         let tri_count = self.tList.len();
 
         // Scan the faces, and shove in the UV's into the vertices
@@ -1395,7 +1588,6 @@ impl EditTriMesh {
             let tri = &mut self.tList[triIndex];
 
             for i in 0..3 {
-                let v_count = self.vList.len();
                 // Locate vertex
                 let vIndex = tri.v[i].index;
 
@@ -1423,13 +1615,14 @@ impl EditTriMesh {
 
                 // OK, we can't use this vertex - somebody else already has
                 // it "claimed" with different UV's.  First, we'll search
-                // for another vertex with the same position.  Yikes -
-                // this requires a linear search through the vertex list.
-                // Luckily, this should not happen the majority of the time.
+                // for another vertex with the same position.  Only the
+                // vertices in the same bucket can match, and they must
+                // still match exactly.
 
                 let temp_vec = self.vList[vIndex].clone();
+                let bucket = buckets.entry(geometryKey(&temp_vec)).or_default();
                 let mut foundOne = false;
-                for newIndex in 0..v_count {
+                for &newIndex in bucket.iter() {
                     let newPtr = &mut self.vList[newIndex];
 
                     // Is the position and normal correct?
@@ -1478,6 +1671,7 @@ impl EditTriMesh {
                     newVertex.v = tri.v[i].v;
                     self.vList.push(newVertex);
                     tri.v[i].index = self.vList.len() - 1;
+                    bucket.push(tri.v[i].index);
                 }
             }
         }
@@ -1491,104 +1685,100 @@ impl EditTriMesh {
         self.computeVertexNormals();
     }
 
-    /*
-        /////////////////////////////////////////////////////////////////////////////
-        //
-        // EditTriMesh members - Debugging
-        //
-        /////////////////////////////////////////////////////////////////////////////
-
-        pub fn validityCheck() {
-        char	errMsg[256];
-        if (!validityCheck(errMsg)) {
-        ABORT("EditTriMesh failed validity check:\n%s", errMsg);
-        }
-        }
-
-        bool	validityCheck(char *returnErrMsg) {
-        return true;
-        }
-    */
-}
-/*
+    /////////////////////////////////////////////////////////////////////////////
+    //
+    // EditTriMesh members - Debugging
+    //
+    /////////////////////////////////////////////////////////////////////////////
 
     //---------------------------------------------------------------------------
-    // operator=
+    // index_check
     //
-    // Assignment operator - make a copy of the mesh
-
-    EditTriMesh &operator=(EditTriMesh &src) {
-    int	i;
-
-    // Start by freeing up what we already have
-
-    empty();
-
-    // Copy materials and parts first.  We copy these stupidly,
-    // since the lists probably won't be very big
-
-    setMaterialCount(src.materialCount());
-    for (i = 0 ; i < materialCount() ; ++i) {
-    material(i) = src.material(i);
-    }
-
-    setPartCount(src.partCount());
-    for (i = 0 ; i < partCount() ; ++i) {
-    part(i) = src.part(i);
-    }
-
-    // Make sure vertex list isn't empty
-
-    if (src.vertexCount() > 0) {
-
-    // Compute size in bytes
-
-    int	bytes = src.vertexCount() * sizeof(*vList);
-
-    // Allocate it.  We don't use setVertexCount(), since
-    // that initializes the list, which we don't need
+    // Check that all of the indices in the triangle list are in range.  A
+    // material index of usize::MAX is the "unset" value from Tri::default()
+    // and is allowed.  Degenerate triangles are not an error here, since
+    // deleteDegenerateTris can clean them up.  Returns a message describing
+    // the first problem found
+    pub fn index_check(&self) -> Result<(), String> {
+        for (i, tri) in self.tList.iter().enumerate() {
+            for j in 0..3 {
+                if tri.v[j].index >= self.vList.len() {
+                    return Err(format!(
+                        "Tri {} vertex {} has index {}, but there are only {} vertices",
+                        i,
+                        j,
+                        tri.v[j].index,
+                        self.vList.len()
+                    ));
+                }
+            }
 
-    vList = (Vertex *)::malloc(bytes);
-    if (vList == NULL) {
-    ABORT("Out of memory");
-    }
-    vCount = src.vertexCount();
-    vAlloc = vCount;
+            if tri.material != usize::MAX && tri.material >= self.mList.len() {
+                return Err(format!(
+                    "Tri {} has material {}, but there are only {} materials",
+                    i,
+                    tri.material,
+                    self.mList.len()
+                ));
+            }
 
-    // Blast copy it
+            if tri.part >= self.pList.len() {
+                return Err(format!(
+                    "Tri {} has part {}, but there are only {} parts",
+                    i,
+                    tri.part,
+                    self.pList.len()
+                ));
+            }
+        }
 
-    memcpy(vList, src.vList, bytes);
+        Ok(())
     }
 
-    // Make sure face list isn't empty
-
-    if (src.triCount() > 0) {
-
-    // Compute size in bytes
-
-    int	bytes = src.triCount() * sizeof(*tList);
-
-    // Allocate it.  We don't use setVertexCount(), since
-    // that initializes the list, which we don't need
+    //---------------------------------------------------------------------------
+    // validity_check
+    //
+    // Run index_check, and also make sure that no triangles are degenerate
+    pub fn validity_check(&self) -> Result<(), String> {
+        self.index_check()?;
+
+        for (i, tri) in self.tList.iter().enumerate() {
+            if tri.isDegenerate() {
+                return Err(format!(
+                    "Tri {} is degenerate, with vertex indices {}, {}, {}",
+                    i, tri.v[0].index, tri.v[1].index, tri.v[2].index
+                ));
+            }
+        }
 
-    tList = (Tri *)::malloc(bytes);
-    if (tList == NULL) {
-    ABORT("Out of memory");
+        Ok(())
     }
-    tCount = src.triCount();
-    tAlloc = tCount;
-
-    // Blast copy it
+}
 
-    memcpy(tList, src.tList, bytes);
+//---------------------------------------------------------------------------
+// Clone
+//
+// clone_from gives the same result as clone(), but reuses the lists the
+// destination has already allocated, which saves a lot of reallocation
+// when converting many meshes in a row, as in dst.clone_from(&src)
+impl Clone for EditTriMesh {
+    fn clone(&self) -> Self {
+        EditTriMesh {
+            vList: self.vList.clone(),
+            tList: self.tList.clone(),
+            mList: self.mList.clone(),
+            pList: self.pList.clone(),
+        }
     }
 
-    // Return reference to l-value, as per C convention
-
-    return *this;
+    fn clone_from(&mut self, source: &Self) {
+        self.empty();
+        self.vList.extend_from_slice(&source.vList);
+        self.tList.extend_from_slice(&source.tList);
+        self.mList.extend_from_slice(&source.mList);
+        self.pList.extend_from_slice(&source.pList);
     }
-
-*/
+}
 
 /////////////////////////////////////////////////////////////////////////////
 //
@@ -1649,6 +1839,29 @@ pub fn triCompareByMaterial(a: &Tri, b: &Tri) -> Ordering {
     Ordering::Equal
 }
 
+//---------------------------------------------------------------------------
+// geometryKey
+//
+// Hash key for a vertex's position and normal, used by copyUvsIntoVertices.
+// The components are quantized to a 1/1024 grid, so vertices that are
+// nearly the same usually share a bucket, and vertices that are exactly
+// the same always do.  The key only narrows down the search: vertices
+// still have to match exactly to be shared, the same as the original
+// linear search, so the quantization never changes the result
+const GEOMETRY_KEY_SCALE: f32 = 1024.0;
+
+fn geometryKey(v: &Vertex) -> [i32; 6] {
+    let q = |x: f32| (x * GEOMETRY_KEY_SCALE).round() as i32;
+    [
+        q(v.p.x),
+        q(v.p.y),
+        q(v.p.z),
+        q(v.normal.x),
+        q(v.normal.y),
+        q(v.normal.z),
+    ]
+}
+
 //---------------------------------------------------------------------------
 // skipLine
 //
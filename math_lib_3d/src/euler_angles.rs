@@ -5,15 +5,29 @@ use crate::quaternion::Quaternion;
 use crate::rotation_matrix::RotationMatrix;
 use crate::utils::*;
 use std::f32::consts::*;
+use std::fmt;
+use std::ops;
 
 /// Represents a heading-pitch-bank Euler angle triangle
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EulerAngles {
     pub heading: f32,
     pub pitch: f32,
     pub bank: f32,
 }
 
+// Wrap an angle into (-pi, pi].  wrap_pi can return -pi, which we
+// move to the other end of the range for canonize
+fn wrap_canonical(theta: f32) -> f32 {
+    let theta = wrap_pi(theta);
+    if theta <= -PI {
+        PI
+    } else {
+        theta
+    }
+}
+
 impl EulerAngles {
     pub fn identity() -> EulerAngles {
         EulerAngles {
@@ -23,6 +37,25 @@ impl EulerAngles {
         }
     }
 
+    // Construct from angles given in degrees, for humans and config files.
+    // Internally, everything is in radians
+    pub fn from_degrees(heading: f32, pitch: f32, bank: f32) -> EulerAngles {
+        EulerAngles {
+            heading: heading.to_radians(),
+            pitch: pitch.to_radians(),
+            bank: bank.to_radians(),
+        }
+    }
+
+    // Return the (heading, pitch, bank) triple in degrees
+    pub fn to_degrees(&self) -> (f32, f32, f32) {
+        (
+            self.heading.to_degrees(),
+            self.pitch.to_degrees(),
+            self.bank.to_degrees(),
+        )
+    }
+
     // Determine "canonical" Euler angle triple
     // Set the Euler angle triple to its "canonical" value. This does not change
     // the meaning of the Euler angles as a representation of Orientation in 3D,
@@ -33,11 +66,11 @@ impl EulerAngles {
 
         // Now, check for "the back side" of the matrix, pitch outside
         // the canonical range of -pi/2 ... pi/2
-        if self.pitch < -FRAC_2_PI {
+        if self.pitch < -FRAC_PI_2 {
             self.pitch = -PI - self.pitch;
             self.heading += PI;
             self.bank += PI;
-        } else if self.pitch > FRAC_2_PI {
+        } else if self.pitch > FRAC_PI_2 {
             self.pitch = PI - self.pitch;
             self.heading += PI;
             self.bank += PI;
@@ -45,19 +78,65 @@ impl EulerAngles {
 
         // OK, now check for the gimbal lock case (within a slight
         // tolerance)
-        if (self.pitch).abs() > FRAC_2_PI - 1e-4 {
+        if (self.pitch).abs() > FRAC_PI_2 - 1e-4 {
             // We are in gimbal lock. Assign all rotation
-            // about the vertical axis to heading
-            self.heading += self.bank;
+            // about the vertical axis to heading.  Bank is about the
+            // object z axis, which has been pitched onto -y (looking
+            // down) or +y (looking up), so its sign depends on which
+            if self.pitch > 0.0 {
+                self.heading -= self.bank;
+            } else {
+                self.heading += self.bank;
+            }
             self.bank = 0.0;
         } else {
             // Not in gimbal lock. Wrap the bank angle in
             // canonical range
-            self.bank = wrap_pi(self.bank);
+            self.bank = wrap_canonical(self.bank);
         }
 
         // Wrap heading in canonical range
-        self.heading = wrap_pi(self.heading);
+        self.heading = wrap_canonical(self.heading);
+
+        debug_assert!(self.is_canonical());
+    }
+
+    // Check if the Euler angle triple is in the canonical set: heading and
+    // bank in (-pi, pi], pitch in -pi/2...pi/2, and bank of zero in gimbal
+    // lock (using the same tolerance as canonize).  -pi is left out since
+    // it is the same angle as pi, so each orientation has only one
+    // canonical triple.  See 10.3.4
+    pub fn is_canonical(&self) -> bool {
+        let in_gimbal_lock = self.pitch.abs() > FRAC_PI_2 - 1e-4;
+
+        self.heading > -PI
+            && self.heading <= PI
+            && (-FRAC_PI_2..=FRAC_PI_2).contains(&self.pitch)
+            && self.bank > -PI
+            && self.bank <= PI
+            && (!in_gimbal_lock || self.bank == 0.0)
+    }
+
+    // Return the quaternion that performs the object->inertial rotation for
+    // this orientation.  Same as Quaternion::set_to_rotate_object_to_inertial
+    pub fn to_object_to_inertial_quaternion(&self) -> Quaternion {
+        let mut q = Quaternion::identity();
+        q.set_to_rotate_object_to_inertial(self.clone());
+        q
+    }
+
+    // Return the (inertial->object) rotation matrix for this orientation.
+    // Same as RotationMatrix::from_euler_angles
+    pub fn to_rotation_matrix(&self) -> RotationMatrix {
+        RotationMatrix::from_euler_angles(self)
+    }
+
+    // Add the angles componentwise, then canonize the result.  Use this to
+    // apply an angular offset, such as an input delta, to an orientation
+    pub fn wrapped_add(&self, other: &EulerAngles) -> EulerAngles {
+        let mut result = self + other;
+        result.canonize();
+        result
     }
 
     // Setup the Euler angles, given an object->inertial rotation quaternion
@@ -69,7 +148,7 @@ impl EulerAngles {
         if sp.abs() > 0.9999 {
             EulerAngles {
                 // Looking straight up or down
-                pitch: FRAC_2_PI * sp,
+                pitch: FRAC_PI_2 * sp,
                 // Compute heading, slam bank to zero
                 heading: atan2(-q.x * q.z + q.w * q.y, 0.5 - q.y * q.y - q.z * q.z),
                 bank: 0.0,
@@ -81,7 +160,7 @@ impl EulerAngles {
             EulerAngles {
                 pitch: sp.asin(),
                 heading: atan2(q.x * q.z + q.w * q.y, 0.5 - q.x * q.x - q.y * q.y),
-                bank: atan2(q.x * q.y + q.w * q.z, 0.5 - q.x * q.x - q.z * q.z).atan(),
+                bank: atan2(q.x * q.y + q.w * q.z, 0.5 - q.x * q.x - q.z * q.z),
             }
         }
     }
@@ -95,7 +174,7 @@ impl EulerAngles {
         if sp.abs() > 0.9999 {
             EulerAngles {
                 // Looking straight up or down
-                pitch: FRAC_2_PI * sp,
+                pitch: FRAC_PI_2 * sp,
                 // Compute heading, slam bank to zero
                 heading: atan2(-q.x * q.z - q.w * q.y, 0.5 - q.y * q.y - q.z * q.z),
                 bank: 0.0,
@@ -119,11 +198,11 @@ impl EulerAngles {
 
         let sp = -m.m23;
 
-        // Check for Gimbal lock
-        if sp.abs() > 9.99999 {
+        // Check for Gimbal lock, giving slight tolerance for numerical imprecision
+        if sp.abs() > 0.9999 {
             EulerAngles {
                 // Looking straight up or down
-                pitch: FRAC_2_PI * sp,
+                pitch: FRAC_PI_2 * sp,
                 // Compute heading, slam bank to zero
                 heading: atan2(-m.m31, m.m11),
                 bank: 0.0,
@@ -134,23 +213,27 @@ impl EulerAngles {
             // checking for Gimbal lock
 
             EulerAngles {
-                heading: atan2(m.m13, m.m33).atan(),
+                heading: atan2(m.m13, m.m33),
                 pitch: sp.asin(),
                 bank: atan2(m.m21, m.m22),
             }
         }
     }
 
-    // Setup the Euler angles, given a rotation matrix.
+    // Setup the Euler angles, given a rotation matrix.  The matrix is
+    // assumed to be orthonormal, see RotationMatrix::is_valid.  This isn't
+    // checked; anything else gives meaningless angles, so call
+    // RotationMatrix::orthonormalize first if the matrix might have drifted
+    // or contains scale or skew
     pub fn from_rotation_matrix(m: &RotationMatrix) -> EulerAngles {
         // Extract sin(pitch) from m23.
         let sp = -m.m23;
 
-        // Check for Gimbal lock
-        if sp.abs() > 9.99999 {
+        // Check for Gimbal lock, giving slight tolerance for numerical imprecision
+        if sp.abs() > 0.9999 {
             EulerAngles {
                 // Looking straight up or down
-                pitch: FRAC_2_PI * sp,
+                pitch: FRAC_PI_2 * sp,
                 // Compute heading, slam bank to zero
                 heading: atan2(-m.m31, m.m11),
                 bank: 0.0,
@@ -167,3 +250,65 @@ impl EulerAngles {
         }
     }
 }
+
+impl Default for EulerAngles {
+    fn default() -> Self {
+        EulerAngles::identity()
+    }
+}
+
+// Print the angles in degrees, as in "h=30.0° p=0.0° b=0.0°".  One decimal
+// place is printed, unless a precision is given, as in {:.3}
+impl fmt::Display for EulerAngles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let p = f.precision().unwrap_or(1);
+        let (heading, pitch, bank) = self.to_degrees();
+        write!(f, "h={:.p$}° p={:.p$}° b={:.p$}°", heading, pitch, bank)
+    }
+}
+
+// EulerAngles + EulerAngles, EulerAngles - EulerAngles
+//
+// Componentwise.  This is fine for offsets and angular velocities, but the
+// sum of two orientations is generally not canonical, and the angles can
+// grow without bound as offsets accumulate.  You usually want to canonize()
+// afterward, or use wrapped_add()
+impl ops::Add<&EulerAngles> for &EulerAngles {
+    type Output = EulerAngles;
+
+    fn add(self, other: &EulerAngles) -> Self::Output {
+        EulerAngles {
+            heading: self.heading + other.heading,
+            pitch: self.pitch + other.pitch,
+            bank: self.bank + other.bank,
+        }
+    }
+}
+
+impl ops::Add for EulerAngles {
+    type Output = EulerAngles;
+
+    fn add(self, other: EulerAngles) -> Self::Output {
+        &self + &other
+    }
+}
+
+impl ops::Sub<&EulerAngles> for &EulerAngles {
+    type Output = EulerAngles;
+
+    fn sub(self, other: &EulerAngles) -> Self::Output {
+        EulerAngles {
+            heading: self.heading - other.heading,
+            pitch: self.pitch - other.pitch,
+            bank: self.bank - other.bank,
+        }
+    }
+}
+
+impl ops::Sub for EulerAngles {
+    type Output = EulerAngles;
+
+    fn sub(self, other: EulerAngles) -> Self::Output {
+        &self - &other
+    }
+}
@@ -0,0 +1,105 @@
+// Conversions between our math types and the glam crate.  Only compiled
+// with the "glam" feature enabled.
+
+use crate::matrix4x3::Matrix4x3;
+use crate::quaternion::Quaternion;
+use crate::vector3::Vector3;
+use glam::{Mat4, Quat, Vec3, Vec4};
+
+impl From<Vec3> for Vector3 {
+    fn from(v: Vec3) -> Self {
+        Vector3 {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<Vector3> for Vec3 {
+    fn from(v: Vector3) -> Self {
+        Vec3::new(v.x, v.y, v.z)
+    }
+}
+
+//---------------------------------------------------------------------------
+// Matrix4x3 <-> Mat4
+//
+// Matrix4x3 uses row vectors, with the assumed rightmost column [0 0 0 1]
+// (see the notes at the top of matrix4x3.rs):
+//
+//                 | m11 m12 m13 0 |
+//     [ x y z 1 ] | m21 m22 m23 0 | = [ x' y' z' 1 ]
+//                 | m31 m32 m33 0 |
+//                 | tx  ty  tz  1 |
+//
+// glam uses column vectors, so the same transform is the transpose of the
+// above.  glam also stores matrices column-major, so each of our rows
+// becomes one glam column:
+//
+//     x_axis = [ m11 m12 m13 0 ]
+//     y_axis = [ m21 m22 m23 0 ]
+//     z_axis = [ m31 m32 m33 0 ]
+//     w_axis = [ tx  ty  tz  1 ]
+//
+// Going the other way, the bottom row of the Mat4 (the w component of each
+// column) is dropped, so projective matrices do not survive the trip.
+impl From<&Matrix4x3> for Mat4 {
+    fn from(m: &Matrix4x3) -> Self {
+        Mat4::from_cols(
+            Vec4::new(m.m11, m.m12, m.m13, 0.0),
+            Vec4::new(m.m21, m.m22, m.m23, 0.0),
+            Vec4::new(m.m31, m.m32, m.m33, 0.0),
+            Vec4::new(m.tx, m.ty, m.tz, 1.0),
+        )
+    }
+}
+
+impl From<Matrix4x3> for Mat4 {
+    fn from(m: Matrix4x3) -> Self {
+        Mat4::from(&m)
+    }
+}
+
+impl From<Mat4> for Matrix4x3 {
+    fn from(m: Mat4) -> Self {
+        Matrix4x3 {
+            m11: m.x_axis.x,
+            m12: m.x_axis.y,
+            m13: m.x_axis.z,
+            m21: m.y_axis.x,
+            m22: m.y_axis.y,
+            m23: m.y_axis.z,
+            m31: m.z_axis.x,
+            m32: m.z_axis.y,
+            m33: m.z_axis.z,
+            tx: m.w_axis.x,
+            ty: m.w_axis.y,
+            tz: m.w_axis.z,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------
+// Quaternion <-> Quat
+//
+// The components map directly, and a quaternion rotates a point the same
+// way in both libraries.  Note that our Quaternion multiplication is
+// reversed from the standard definition (see section 10.4.8), so a * b
+// here corresponds to b * a in glam.
+impl From<Quat> for Quaternion {
+    fn from(q: Quat) -> Self {
+        Quaternion {
+            x: q.x,
+            y: q.y,
+            z: q.z,
+            w: q.w,
+        }
+    }
+}
+
+impl From<Quaternion> for Quat {
+    fn from(q: Quaternion) -> Self {
+        Quat::from_xyzw(q.x, q.y, q.z, q.w)
+    }
+}
@@ -6,12 +6,18 @@ pub mod bitmap;
 pub mod config;
 pub mod edit_tri_mesh;
 pub mod euler_angles;
+#[cfg(feature = "glam")]
+pub mod glam_interop;
 pub mod matrix4x3;
+pub mod matrix4x4;
 pub mod model;
+pub mod plane;
 pub mod quaternion;
+pub mod ray;
 pub mod renderer;
 pub mod rotation_matrix;
 pub mod s3d_handler;
+pub mod sphere;
 pub mod tri_mesh;
 pub mod utils;
 pub mod vector3;
@@ -4,6 +4,7 @@ use crate::euler_angles::EulerAngles;
 use crate::quaternion::Quaternion;
 use crate::rotation_matrix::RotationMatrix;
 use crate::vector3::Vector3;
+use std::fmt;
 use std::ops;
 
 /////////////////////////////////////////////////////////////////////////////
@@ -49,7 +50,42 @@ use std::ops;
 //
 /////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug)]
+//---------------------------------------------------------------------------
+// Axis
+//
+// A cardinal axis, used to select the axis for setup_rotate, setup_shear
+// and setup_reflection_from_axis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    // Convert from the 1-based axis index used by the C++ version
+    // (1 => x, 2 => y, 3 => z).  Returns None for anything else.
+    pub fn from_index(index: i32) -> Option<Axis> {
+        Axis::try_from(index).ok()
+    }
+}
+
+// Same as from_index.  The error is the bad index
+impl TryFrom<i32> for Axis {
+    type Error = i32;
+
+    fn try_from(index: i32) -> Result<Self, Self::Error> {
+        match index {
+            1 => Ok(Axis::X),
+            2 => Ok(Axis::Y),
+            3 => Ok(Axis::Z),
+            _ => Err(index),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Matrix4x3 {
     pub m11: f32,
     pub m12: f32,
@@ -79,7 +115,7 @@ impl Matrix4x3 {
             m33: 1.0,
             tx: 0.0,
             ty: 0.0,
-            tz: 1.0,
+            tz: 0.0,
         }
     }
 
@@ -95,7 +131,26 @@ impl Matrix4x3 {
         self.m33 = 1.0;
         self.tx = 0.0;
         self.ty = 0.0;
-        self.tz = 1.0;
+        self.tz = 0.0;
+    }
+
+    //---------------------------------------------------------------------------
+    // approx_eq
+    //
+    // Return true if each element is within epsilon of the other matrix.
+    // Use this instead of == for results of floating point computations.
+    pub fn approx_eq(&self, other: &Matrix4x3, epsilon: f32) -> bool {
+        let a = [
+            self.m11, self.m12, self.m13, self.m21, self.m22, self.m23, self.m31, self.m32,
+            self.m33, self.tx, self.ty, self.tz,
+        ];
+        let b = [
+            other.m11, other.m12, other.m13, other.m21, other.m22, other.m23, other.m31, other.m32,
+            other.m33, other.tx, other.ty, other.tz,
+        ];
+        a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| (x - y).abs() <= epsilon)
     }
 
     //---------------------------------------------------------------------------
@@ -140,6 +195,214 @@ impl Matrix4x3 {
         self.tz = d.z;
     }
 
+    //---------------------------------------------------------------------------
+    // is_orthonormal
+    //
+    // Check if the 3x3 portion is orthonormal - each row is unit length and
+    // perpendicular to the others.  This is true for rotations and
+    // reflections, but not for scale, skew or projection.  Functions such
+    // as transpose_rigid() and get_position_from_parent_to_local_matrix()
+    // assume this.
+    //
+    // See 9.3 for more info.
+    pub fn is_orthonormal(&self, epsilon: f32) -> bool {
+        let r1 = self.right();
+        let r2 = self.up();
+        let r3 = self.forward();
+
+        r1.is_unit(epsilon)
+            && r2.is_unit(epsilon)
+            && r3.is_unit(epsilon)
+            && r1.dot(&r2).abs() < epsilon
+            && r1.dot(&r3).abs() < epsilon
+            && r2.dot(&r3).abs() < epsilon
+    }
+
+    //---------------------------------------------------------------------------
+    // has_negative_scale
+    //
+    // Check if the matrix contains a reflection (mirroring).  A negative
+    // determinant means the transform flips the handedness of the space, and
+    // with it the winding order of triangles, so clockwise front faces
+    // become counterclockwise.  Anything doing backface culling must account
+    // for this.
+    //
+    // See 9.1.2 for more info.
+    pub fn has_negative_scale(&self) -> bool {
+        determinant(self) < 0.0
+    }
+
+    //---------------------------------------------------------------------------
+    // signed_volume_scale
+    //
+    // Return the factor by which the matrix scales volumes.  This is just
+    // the determinant.  It is negative if the matrix contains a reflection,
+    // and zero if the matrix flattens space (such as a projection).
+    //
+    // See 9.1.2 for more info.
+    pub fn signed_volume_scale(&self) -> f32 {
+        determinant(self)
+    }
+
+    //---------------------------------------------------------------------------
+    // is_singular
+    //
+    // Check if the matrix is singular (or very nearly so), and therefore has
+    // no inverse.
+    pub fn is_singular(&self, epsilon: f32) -> bool {
+        determinant(self).abs() <= epsilon
+    }
+
+    //---------------------------------------------------------------------------
+    // right / up / forward
+    //
+    // Return the basis vectors of the local frame, expressed in the parent
+    // frame.  Since we use row vectors, transforming the local x axis
+    // [1 0 0] picks out the first row of the 3x3 portion, and so on.  With
+    // our left-handed conventions (+x right, +y up, +z forward), this gives:
+    //
+    //     row 1 (m11 m12 m13) => right
+    //     row 2 (m21 m22 m23) => up
+    //     row 3 (m31 m32 m33) => forward
+    //
+    // This only makes sense for a local -> parent matrix, such as an
+    // object -> world matrix.  For a parent -> local matrix, the axes are
+    // the columns instead.  The vectors are not normalized, so any scale
+    // in the matrix is carried along.
+    pub fn right(&self) -> Vector3 {
+        Vector3::new(self.m11, self.m12, self.m13)
+    }
+
+    pub fn up(&self) -> Vector3 {
+        Vector3::new(self.m21, self.m22, self.m23)
+    }
+
+    pub fn forward(&self) -> Vector3 {
+        Vector3::new(self.m31, self.m32, self.m33)
+    }
+
+    //---------------------------------------------------------------------------
+    // from_basis
+    //
+    // Build a local -> parent matrix from the local frame's axes and origin,
+    // all expressed in the parent frame.  This is the inverse of the
+    // right() / up() / forward() accessors: the axes become the rows of the
+    // 3x3 portion, and origin becomes the translation.
+    //
+    // The basis should be (roughly) orthonormal, which is checked in debug
+    // builds.
+    pub fn from_basis(
+        right: &Vector3,
+        up: &Vector3,
+        forward: &Vector3,
+        origin: &Vector3,
+    ) -> Matrix4x3 {
+        let m = Matrix4x3 {
+            m11: right.x,
+            m12: right.y,
+            m13: right.z,
+            m21: up.x,
+            m22: up.y,
+            m23: up.z,
+            m31: forward.x,
+            m32: forward.y,
+            m33: forward.z,
+            tx: origin.x,
+            ty: origin.y,
+            tz: origin.z,
+        };
+        debug_assert!(m.is_orthonormal(0.01));
+        m
+    }
+
+    //---------------------------------------------------------------------------
+    // transpose_3x3
+    //
+    // Return the transpose of the 3x3 (linear transformation) portion of the
+    // matrix.  For a rotation, this converts between a local -> parent and
+    // a parent -> local rotation.
+    //
+    // The translation row is copied unchanged.  It is NOT a valid
+    // translation for the transposed matrix - use transpose_rigid() if
+    // you want the inverse of a rigid transform.
+    pub fn transpose_3x3(&self) -> Matrix4x3 {
+        Matrix4x3 {
+            m11: self.m11,
+            m12: self.m21,
+            m13: self.m31,
+            m21: self.m12,
+            m22: self.m22,
+            m23: self.m32,
+            m31: self.m13,
+            m32: self.m23,
+            m33: self.m33,
+            tx: self.tx,
+            ty: self.ty,
+            tz: self.tz,
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // transpose_rigid
+    //
+    // Return the inverse of a rigid transformation (rotation and translation
+    // only).  The inverse of an orthonormal matrix is its transpose, so we
+    // transpose the 3x3 portion and then rotate the negated translation
+    // into the new frame.  This is much cheaper than inverse(), but the
+    // result is garbage if the 3x3 portion contains scale or skew.
+    //
+    // See 9.3 for more info.
+    pub fn transpose_rigid(&self) -> Matrix4x3 {
+        let mut r = self.transpose_3x3();
+        r.tx = -(self.tx * r.m11 + self.ty * r.m21 + self.tz * r.m31);
+        r.ty = -(self.tx * r.m12 + self.ty * r.m22 + self.tz * r.m32);
+        r.tz = -(self.tx * r.m13 + self.ty * r.m23 + self.tz * r.m33);
+        r
+    }
+
+    //---------------------------------------------------------------------------
+    // inverse_rigid
+    //
+    // Fast inverse of a rigid transformation, such as a camera or object
+    // matrix.  The 3x3 portion MUST be orthonormal (rotation only, no scale
+    // or skew), which is checked in debug builds.  This is the same as
+    // transpose_rigid(), and is much cheaper and numerically cleaner than
+    // the general inverse(), which computes the full adjoint.
+    pub fn inverse_rigid(&self) -> Matrix4x3 {
+        debug_assert!(self.is_orthonormal(0.01));
+        self.transpose_rigid()
+    }
+
+    //---------------------------------------------------------------------------
+    // orthonormalize
+    //
+    // Force the 3x3 portion of the matrix back to orthonormal using
+    // Gram-Schmidt.  After many successive concatenations floating point
+    // error creeps in and the axes drift away from unit length and
+    // perpendicular.  Each row is made perpendicular to the rows before it
+    // and then normalized, so the first row keeps its direction exactly.
+    //
+    // The translation portion is not modified.
+    //
+    // See 9.3.3 for more info.
+    pub fn orthonormalize(&mut self) {
+        let [r1, r2, r3] = gram_schmidt([
+            Vector3::new(self.m11, self.m12, self.m13),
+            Vector3::new(self.m21, self.m22, self.m23),
+            Vector3::new(self.m31, self.m32, self.m33),
+        ]);
+
+        self.m11 = r1.x;
+        self.m12 = r1.y;
+        self.m13 = r1.z;
+        self.m21 = r2.x;
+        self.m22 = r2.y;
+        self.m23 = r2.z;
+        self.m31 = r3.x;
+        self.m32 = r3.y;
+        self.m33 = r3.z;
+    }
+
     //---------------------------------------------------------------------------
     // setupLocalToParent
     //
@@ -193,6 +456,84 @@ impl Matrix4x3 {
         self.tz = pos.z;
     }
 
+    //---------------------------------------------------------------------------
+    // from_trs
+    //
+    // Create a local -> parent matrix that scales, then rotates, then
+    // translates.  This is the usual object -> world matrix for a node in a
+    // scene graph.  Since we use row vectors, the concatenation reads left
+    // to right:
+    //
+    //     M = S R T
+    //
+    // Scaling first means each row of the rotation portion is multiplied
+    // by the scale factor for that local axis.
+    pub fn from_trs(translation: &Vector3, orient: &EulerAngles, scale: &Vector3) -> Matrix4x3 {
+        let mut m = Matrix4x3::identity();
+        m.setup_local_to_parent_euler_angles(translation, orient);
+
+        m.m11 *= scale.x;
+        m.m12 *= scale.x;
+        m.m13 *= scale.x;
+        m.m21 *= scale.y;
+        m.m22 *= scale.y;
+        m.m23 *= scale.y;
+        m.m31 *= scale.z;
+        m.m32 *= scale.z;
+        m.m33 *= scale.z;
+
+        m
+    }
+
+    //---------------------------------------------------------------------------
+    // decompose
+    //
+    // Split a local -> parent matrix back into translation, orientation and
+    // scale, the inverse of from_trs.  Returns (translation, orient, scale).
+    //
+    // The scale on each axis is the length of the corresponding row.  If
+    // the matrix contains a reflection, the x scale is returned negative.
+    // Any skew in the matrix cannot be represented and is lost: the rows
+    // are orthonormalized before extracting the orientation, favoring the
+    // x axis, then y.  An axis with zero scale has no direction, so one is
+    // made up perpendicular to the others.
+    pub fn decompose(&self) -> (Vector3, EulerAngles, Vector3) {
+        let mut rows = [self.right(), self.up(), self.forward()];
+
+        let mut scale = Vector3::new(
+            rows[0].magnitude(),
+            rows[1].magnitude(),
+            rows[2].magnitude(),
+        );
+        if self.has_negative_scale() {
+            scale.x = -scale.x;
+            rows[0] = -rows[0];
+        }
+
+        let [right, up, forward] = orthonormal_basis(rows);
+
+        // The rows now form a local -> parent (object -> inertial) rotation.
+        // RotationMatrix stores the inertial -> object direction, which is
+        // the transpose
+        let r = RotationMatrix {
+            m11: right.x,
+            m12: up.x,
+            m13: forward.x,
+            m21: right.y,
+            m22: up.y,
+            m23: forward.y,
+            m31: right.z,
+            m32: up.z,
+            m33: forward.z,
+        };
+
+        (
+            get_translation(self),
+            EulerAngles::from_rotation_matrix(&r),
+            scale,
+        )
+    }
+
     //---------------------------------------------------------------------------
     // setupParentToLocal
     //
@@ -249,15 +590,81 @@ impl Matrix4x3 {
     }
 
     //---------------------------------------------------------------------------
-    // setupRotate
+    // setup_look_along
     //
-    // Setup the matrix to perform a rotation about a cardinal axis
+    // Setup the matrix to orient an object so that its local +z (forward)
+    // axis points along forward, such as aligning a projectile with its
+    // velocity.  This is a local -> parent rotation, so the rows of the 3x3
+    // portion are the object's right, up and forward axes.  up is a hint
+    // for which way is "up" in the parent space - it does not need to be
+    // unit length or perpendicular to forward.
     //
-    // The axis of rotation is specified using a 1-based index:
+    // If forward is parallel to up, the hint is useless, so we fall back to
+    // the parent +z axis (or +x if forward is along z).  A zero forward
+    // vector is treated as +z.
     //
-    //	1 => rotate about the x-axis
-    //	2 => rotate about the y-axis
-    //	3 => rotate about the z-axis
+    // The translation portion is reset.
+    pub fn setup_look_along(&mut self, forward: &Vector3, up: &Vector3) {
+        // Compute the forward axis
+        let mut f = *forward;
+        if f.dot(&f) < 0.000001 {
+            f = Vector3::new(0.0, 0.0, 1.0);
+        }
+        f.normalize();
+
+        // Right is perpendicular to both up and forward.  Check for the
+        // degenerate case where they are parallel
+        let mut r = up.cross(&f);
+        if r.dot(&r) < 0.000001 {
+            let fallback = if f.z.abs() < 0.9 {
+                Vector3::new(0.0, 0.0, 1.0)
+            } else {
+                Vector3::new(1.0, 0.0, 0.0)
+            };
+            r = fallback.cross(&f);
+        }
+        r.normalize();
+
+        // Recompute up so the axes are exactly perpendicular
+        let u = f.cross(&r);
+
+        self.m11 = r.x;
+        self.m12 = r.y;
+        self.m13 = r.z;
+        self.m21 = u.x;
+        self.m22 = u.y;
+        self.m23 = u.z;
+        self.m31 = f.x;
+        self.m32 = f.y;
+        self.m33 = f.z;
+
+        // Reset the translation portion
+        self.tx = 0.0;
+        self.ty = 0.0;
+        self.tz = 0.0;
+    }
+
+    //---------------------------------------------------------------------------
+    // look_at
+    //
+    // Create a world -> camera (parent -> local) matrix for a camera at eye
+    // looking toward target.  We use the same conventions as the rest of the
+    // library: in camera space, +z points forward, +y up and +x to the right
+    // (left-handed).  up is a hint for which way is "up" in the world, and
+    // the degenerate cases are handled as in setup_look_along.
+    pub fn look_at(eye: &Vector3, target: &Vector3, up: &Vector3) -> Matrix4x3 {
+        // Build the camera -> world matrix, then invert it.  It's a rigid
+        // transform, so the cheap inverse is exact
+        let mut camera_to_world = Matrix4x3::identity();
+        camera_to_world.setup_look_along(&(target - eye), up);
+        camera_to_world.set_translation(eye);
+        camera_to_world.transpose_rigid()
+    }
+
+    //---------------------------------------------------------------------------
+    // setupRotate
+    //
+    // Setup the matrix to perform a rotation about a cardinal axis
     //
     // theta is the amount of rotation, in radians.  The left-hand rule is
     // used to define "positive" rotation.
@@ -265,14 +672,14 @@ impl Matrix4x3 {
     // The translation portion is reset.
     //
     // See 8.2.2 for more info.
-    pub fn setup_rotate_axis(&mut self, axis: i32, theta: f32) {
+    pub fn setup_rotate(&mut self, axis: Axis, theta: f32) {
         // Get sin and cosine of rotation angle
         let (sin, cos) = theta.sin_cos();
 
         // Check which axis they are rotating about
         match axis {
             // Rotate about the x-axis
-            1 => {
+            Axis::X => {
                 self.m11 = 1.0;
                 self.m12 = 0.0;
                 self.m13 = 0.0;
@@ -283,7 +690,7 @@ impl Matrix4x3 {
                 self.m32 = -sin;
                 self.m33 = cos;
             }
-            2 => {
+            Axis::Y => {
                 // Rotate about the y-axis
                 self.m11 = cos;
                 self.m12 = 0.0;
@@ -295,7 +702,7 @@ impl Matrix4x3 {
                 self.m32 = 0.0;
                 self.m33 = cos;
             }
-            3 => {
+            Axis::Z => {
                 // Rotate about the z-axis
                 self.m11 = cos;
                 self.m12 = sin;
@@ -307,10 +714,6 @@ impl Matrix4x3 {
                 self.m32 = 0.0;
                 self.m33 = 1.0;
             }
-            _ => {
-                // bogus axis index
-                assert!(false);
-            }
         }
 
         // Reset the translation portion
@@ -319,6 +722,26 @@ impl Matrix4x3 {
         self.tz = 0.0;
     }
 
+    //---------------------------------------------------------------------------
+    // setup_rotate_axis
+    //
+    // Same as setup_rotate, with the axis specified using a 1-based index:
+    //
+    //	1 => rotate about the x-axis
+    //	2 => rotate about the y-axis
+    //	3 => rotate about the z-axis
+    //
+    // Returns false and leaves the matrix alone on any other index.
+    pub fn setup_rotate_axis(&mut self, axis: i32, theta: f32) -> bool {
+        match Axis::try_from(axis) {
+            Ok(axis) => {
+                self.setup_rotate(axis, theta);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     //---------------------------------------------------------------------------
     // setupRotate
     //
@@ -368,6 +791,21 @@ impl Matrix4x3 {
         self.tz = 0.0;
     }
 
+    //---------------------------------------------------------------------------
+    // from_axis_angle
+    //
+    // Return a new matrix that rotates about an arbitrary axis through the
+    // origin.  Same as setup_rotate_from_vector, but doesn't need an
+    // existing matrix to fill in.  axis must be a unit vector, and theta
+    // is in radians, with the left-hand rule defining positive rotation.
+    //
+    // The translation portion is zero.
+    pub fn from_axis_angle(axis: &Vector3, theta: f32) -> Matrix4x3 {
+        let mut m = Matrix4x3::identity();
+        m.setup_rotate_from_vector(axis, theta);
+        m
+    }
+
     //---------------------------------------------------------------------------
     // from_quaternion
     //
@@ -478,21 +916,21 @@ impl Matrix4x3 {
     //
     // Setup the matrix to perform a shear
     //
-    // The type of shear is specified by the 1-based "axis" index.  The effect
-    // of transforming a point by the matrix is described by the pseudocode
+    // The type of shear is specified by the axis.  The effect of
+    // transforming a point by the matrix is described by the pseudocode
     // below:
     //
-    //	axis == 1  =>  y += s*x, z += t*x
-    //	axis == 2  =>  x += s*y, z += t*y
-    //	axis == 3  =>  x += s*z, y += t*z
+    //	Axis::X  =>  y += s*x, z += t*x
+    //	Axis::Y  =>  x += s*y, z += t*y
+    //	Axis::Z  =>  x += s*z, y += t*z
     //
     // The translation portion is reset.
     //
     // See 8.6 for more info.
-    pub fn setup_shear(&mut self, axis: i32, s: f32, t: f32) {
+    pub fn setup_shear(&mut self, axis: Axis, s: f32, t: f32) {
         // Check which type of shear they want
         match axis {
-            1 => {
+            Axis::X => {
                 // Shear y and z using x
                 self.m11 = 1.0;
                 self.m12 = s;
@@ -504,7 +942,7 @@ impl Matrix4x3 {
                 self.m32 = 0.0;
                 self.m33 = 1.0;
             }
-            2 => {
+            Axis::Y => {
                 // Shear x and z using y
                 self.m11 = 1.0;
                 self.m12 = 0.0;
@@ -516,7 +954,7 @@ impl Matrix4x3 {
                 self.m32 = 0.0;
                 self.m33 = 1.0;
             }
-            3 => {
+            Axis::Z => {
                 // Shear x and y using z
                 self.m11 = 1.0;
                 self.m12 = 0.0;
@@ -528,10 +966,6 @@ impl Matrix4x3 {
                 self.m32 = t;
                 self.m33 = 1.0;
             }
-            _ => {
-                // bogus axis index
-                assert!(false);
-            }
         }
 
         // Reset the translation portion
@@ -540,6 +974,22 @@ impl Matrix4x3 {
         self.tz = 0.0;
     }
 
+    //---------------------------------------------------------------------------
+    // setup_shear_axis
+    //
+    // Same as setup_shear, with the axis specified using a 1-based index as
+    // for setup_rotate_axis.  Returns false and leaves the matrix alone on
+    // a bad index.
+    pub fn setup_shear_axis(&mut self, axis: i32, s: f32, t: f32) -> bool {
+        match Axis::try_from(axis) {
+            Ok(axis) => {
+                self.setup_shear(axis, s, t);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     //---------------------------------------------------------------------------
     // setup_projection
     //
@@ -573,26 +1023,48 @@ impl Matrix4x3 {
         self.tz = 0.0;
     }
 
+    //---------------------------------------------------------------------------
+    // setup_projection_plane
+    //
+    // Setup the matrix to perform a projection onto the plane n.p = d, where
+    // n is a unit vector perpendicular to the plane.  This is useful for
+    // flattening an object onto the ground to make a planar shadow.
+    //
+    // Each point is moved along n by its signed distance to the plane:
+    //
+    //     p' = p - ((p.n) - d) n
+    //
+    // The linear portion is the same as for a plane through the origin, and
+    // the d n term becomes the translation, since an offset plane makes the
+    // projection affine.
+    pub fn setup_projection_plane(&mut self, n: &Vector3, d: f32) {
+        self.setup_projection(n);
+
+        self.tx = n.x * d;
+        self.ty = n.y * d;
+        self.tz = n.z * d;
+    }
+
     //---------------------------------------------------------------------------
     // setupReflect
     //
     // Setup the matrix to perform a reflection about a plane parallel
     // to a cardinal plane.
     //
-    // axis is a 1-based index which specifies the plane to project about:
+    // axis specifies the plane to reflect about:
     //
-    //	1 => reflect about the plane x=k
-    //	2 => reflect about the plane y=k
-    //	3 => reflect about the plane z=k
+    //	Axis::X => reflect about the plane x=k
+    //	Axis::Y => reflect about the plane y=k
+    //	Axis::Z => reflect about the plane z=k
     //
     // The translation is set appropriately, since translation must occur if
     // k != 0
     //
     // See 8.5 for more info.
-    pub fn setup_reflection_from_axis(&mut self, axis: i32, k: f32) {
+    pub fn setup_reflection_from_axis(&mut self, axis: Axis, k: f32) {
         // Check which plane they want to reflect about
         match axis {
-            1 => {
+            Axis::X => {
                 // Reflect about the plane x=k
                 self.m11 = -1.0;
                 self.m12 = 0.0;
@@ -608,7 +1080,7 @@ impl Matrix4x3 {
                 self.ty = 0.0;
                 self.tz = 0.0;
             }
-            2 => {
+            Axis::Y => {
                 // Reflect about the plane y=k
                 self.m11 = 1.0;
                 self.m12 = 0.0;
@@ -624,7 +1096,7 @@ impl Matrix4x3 {
                 self.ty = 2.0 * k;
                 self.tz = 0.0;
             }
-            3 => {
+            Axis::Z => {
                 // Reflect about the plane z=k
                 self.m11 = 1.0;
                 self.m12 = 0.0;
@@ -640,10 +1112,22 @@ impl Matrix4x3 {
                 self.ty = 0.0;
                 self.tz = 2.0 * k;
             }
-            _ => {
-                // bogus axis index
-                assert!(false);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // setup_reflection_axis
+    //
+    // Same as setup_reflection_from_axis, with the axis specified using a
+    // 1-based index as for setup_rotate_axis.  Returns false and leaves the
+    // matrix alone on a bad index.
+    pub fn setup_reflection_axis(&mut self, axis: i32, k: f32) -> bool {
+        match Axis::try_from(axis) {
+            Ok(axis) => {
+                self.setup_reflection_from_axis(axis, k);
+                true
             }
+            Err(_) => false,
         }
     }
 
@@ -685,6 +1169,116 @@ impl Matrix4x3 {
         self.ty = 0.0;
         self.tz = 0.0;
     }
+
+    //---------------------------------------------------------------------------
+    // transform_point
+    //
+    // Transform a point, including the translation portion.  This is the
+    // same as p * m.
+    pub fn transform_point(&self, p: &Vector3) -> Vector3 {
+        *p * self
+    }
+
+    //---------------------------------------------------------------------------
+    // transform_points
+    //
+    // Transform a whole list of points in place, such as a vertex buffer
+    pub fn transform_points(&self, points: &mut [Vector3]) {
+        for p in points.iter_mut() {
+            *p = self.transform_point(p);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // transform_points_into
+    //
+    // Transform a list of points, writing the results into a separate
+    // list.  The source is not modified.  Both lists must be the same
+    // length.
+    pub fn transform_points_into(&self, src: &[Vector3], dst: &mut [Vector3]) {
+        assert_eq!(src.len(), dst.len());
+
+        for (s, d) in src.iter().zip(dst.iter_mut()) {
+            *d = self.transform_point(s);
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // transform_direction
+    //
+    // Transform a direction (such as a velocity or an edge vector).  Only
+    // the 3x3 portion is applied - directions have no position, so the
+    // translation is ignored.
+    //
+    // Note that surface normals only transform correctly this way if the
+    // 3x3 portion is orthogonal.  In general, normals must be transformed by
+    // the inverse transpose.
+    pub fn transform_direction(&self, d: &Vector3) -> Vector3 {
+        Vector3 {
+            x: d.x * self.m11 + d.y * self.m21 + d.z * self.m31,
+            y: d.x * self.m12 + d.y * self.m22 + d.z * self.m32,
+            z: d.x * self.m13 + d.y * self.m23 + d.z * self.m33,
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // to_dx_array
+    //
+    // Expand to a 4x4 matrix in the layout of a D3DMATRIX, for uploading to
+    // a graphics API.  Direct3D uses row vectors like we do, and stores
+    // matrices row-major, so the elements are simply our rows in order,
+    // with the assumed rightmost column [ 0 0 0 1 ] filled in:
+    //
+    //     [ m11 m12 m13 0  m21 m22 m23 0  m31 m32 m33 0  tx ty tz 1 ]
+    #[rustfmt::skip]
+    pub fn to_dx_array(&self) -> [f32; 16] {
+        [
+            self.m11, self.m12, self.m13, 0.0,
+            self.m21, self.m22, self.m23, 0.0,
+            self.m31, self.m32, self.m33, 0.0,
+            self.tx,  self.ty,  self.tz,  1.0,
+        ]
+    }
+
+    //---------------------------------------------------------------------------
+    // to_gl_array
+    //
+    // Expand to a column-major 4x4 matrix for OpenGL (or wgpu, glam, etc.)
+    // These use column vectors, so the matrix they expect is the transpose
+    // of ours, with the translation in the rightmost column and [ 0 0 0 1 ]
+    // along the bottom.  Storing that transpose column-major puts each of
+    // our rows into one GL column - which is exactly the same sequence of
+    // floats as to_dx_array().  Two transposes cancel.  We provide both so
+    // the intent at the call site is clear.
+    pub fn to_gl_array(&self) -> [f32; 16] {
+        self.to_dx_array()
+    }
+}
+
+//---------------------------------------------------------------------------
+// Display
+//
+// Print the matrix as a grid of four rows, laid out as in the diagram at
+// the top of this file.  The numbers are printed with 4 decimal places,
+// unless a precision is given, as in {:.2}
+impl fmt::Display for Matrix4x3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let p = f.precision().unwrap_or(4);
+        let w = p + 5;
+        let rows = [
+            [self.m11, self.m12, self.m13],
+            [self.m21, self.m22, self.m23],
+            [self.m31, self.m32, self.m33],
+            [self.tx, self.ty, self.tz],
+        ];
+        for (i, r) in rows.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "| {:w$.p$} {:w$.p$} {:w$.p$} |", r[0], r[1], r[2])?;
+        }
+        Ok(())
+    }
 }
 
 //---------------------------------------------------------------------------
@@ -713,9 +1307,9 @@ impl ops::Mul<&Matrix4x3> for Vector3 {
 //
 impl ops::MulAssign<&Matrix4x3> for Vector3 {
     fn mul_assign(&mut self, m: &Matrix4x3) {
-        self.x = self.x * m.m11 + self.y * m.m21 + self.z * m.m31 + m.tx;
-        self.y = self.x * m.m12 + self.y * m.m22 + self.z * m.m32 + m.ty;
-        self.z = self.x * m.m13 + self.y * m.m23 + self.z * m.m33 + m.tz;
+        // Every output component reads all three inputs, so we must
+        // not overwrite x before computing y and z
+        *self = *self * m;
     }
 }
 
@@ -755,25 +1349,81 @@ impl ops::Mul for Matrix4x3 {
     }
 }
 
+impl ops::Mul<&Matrix4x3> for &Matrix4x3 {
+    type Output = Matrix4x3;
+
+    fn mul(self, b: &Matrix4x3) -> Self::Output {
+        *self * *b
+    }
+}
+
 impl ops::MulAssign for Matrix4x3 {
     fn mul_assign(&mut self, b: Self) {
-        // Compute the upper 3x3 (linear transformation) portion
-        self.m11 = self.m11 * b.m11 + self.m12 * b.m21 + self.m13 * b.m31;
-        self.m12 = self.m11 * b.m12 + self.m12 * b.m22 + self.m13 * b.m32;
-        self.m13 = self.m11 * b.m13 + self.m12 * b.m23 + self.m13 * b.m33;
+        // Each element of the result depends on a whole row of self, so
+        // computing in place would read values we have already
+        // overwritten.  Concatenate into a fresh matrix instead
+        *self = *self * b;
+    }
+}
 
-        self.m21 = self.m21 * b.m11 + self.m22 * b.m21 + self.m23 * b.m31;
-        self.m22 = self.m21 * b.m12 + self.m22 * b.m22 + self.m23 * b.m32;
-        self.m23 = self.m21 * b.m13 + self.m22 * b.m23 + self.m23 * b.m33;
+//---------------------------------------------------------------------------
+// Matrix4x3 + Matrix4x3, Matrix4x3 * scalar
+//
+// Element-wise, across all 12 elements.  These are for blending between
+// transforms, such as a crude approximation to skinning:
+//
+//     m = &(&a * (1.0 - t)) + &(&b * t)
+//
+// Note that the result is generally NOT a valid rigid transform, even if
+// both inputs are.  Blending two rotations shrinks and skews the axes, so
+// call orthonormalize() on the result if you need a pure rotation.
+impl ops::Add<&Matrix4x3> for &Matrix4x3 {
+    type Output = Matrix4x3;
+
+    fn add(self, b: &Matrix4x3) -> Self::Output {
+        Matrix4x3 {
+            m11: self.m11 + b.m11,
+            m12: self.m12 + b.m12,
+            m13: self.m13 + b.m13,
+            m21: self.m21 + b.m21,
+            m22: self.m22 + b.m22,
+            m23: self.m23 + b.m23,
+            m31: self.m31 + b.m31,
+            m32: self.m32 + b.m32,
+            m33: self.m33 + b.m33,
+            tx: self.tx + b.tx,
+            ty: self.ty + b.ty,
+            tz: self.tz + b.tz,
+        }
+    }
+}
+
+impl ops::Mul<f32> for &Matrix4x3 {
+    type Output = Matrix4x3;
+
+    fn mul(self, k: f32) -> Self::Output {
+        Matrix4x3 {
+            m11: self.m11 * k,
+            m12: self.m12 * k,
+            m13: self.m13 * k,
+            m21: self.m21 * k,
+            m22: self.m22 * k,
+            m23: self.m23 * k,
+            m31: self.m31 * k,
+            m32: self.m32 * k,
+            m33: self.m33 * k,
+            tx: self.tx * k,
+            ty: self.ty * k,
+            tz: self.tz * k,
+        }
+    }
+}
 
-        self.m31 = self.m31 * b.m11 + self.m32 * b.m21 + self.m33 * b.m31;
-        self.m32 = self.m31 * b.m12 + self.m32 * b.m22 + self.m33 * b.m32;
-        self.m33 = self.m31 * b.m13 + self.m32 * b.m23 + self.m33 * b.m33;
+impl ops::Mul<&Matrix4x3> for f32 {
+    type Output = Matrix4x3;
 
-        // Compute the translation portion
-        self.tx = self.tx * b.m11 + self.ty * b.m21 + self.tz * b.m31 + b.tx;
-        self.ty = self.tx * b.m12 + self.ty * b.m22 + self.tz * b.m32 + b.ty;
-        self.tz = self.tx * b.m13 + self.ty * b.m23 + self.tz * b.m33 + b.tz;
+    fn mul(self, m: &Matrix4x3) -> Self::Output {
+        m * self
     }
 }
 
@@ -782,6 +1432,10 @@ impl ops::MulAssign for Matrix4x3 {
 //
 // Compute the determinant of the 3x3 portion of the matrix.
 //
+// This is also the determinant of the full 4x4 matrix.  Expanding along
+// the assumed rightmost column [ 0 0 0 1 ] leaves only the 3x3 portion,
+// so the translation never affects it.
+//
 // See 9.1.1 for more info.
 pub fn determinant(m: &Matrix4x3) -> f32 {
     m.m11 * (m.m22 * m.m33 - m.m23 * m.m32)
@@ -789,20 +1443,113 @@ pub fn determinant(m: &Matrix4x3) -> f32 {
         + m.m13 * (m.m21 * m.m32 - m.m22 * m.m31)
 }
 
+//---------------------------------------------------------------------------
+// gram_schmidt
+//
+// Classic Gram-Schmidt on three rows.  The first row is normalized, and
+// each later row has the components along the rows before it removed and
+// is then normalized.  Shared by Matrix4x3::orthonormalize and
+// RotationMatrix::orthonormalize.
+pub(crate) fn gram_schmidt(rows: [Vector3; 3]) -> [Vector3; 3] {
+    let [mut r1, mut r2, mut r3] = rows;
+
+    r1.normalize();
+
+    r2 -= r1 * r2.dot(&r1);
+    r2.normalize();
+
+    r3 -= r1 * r3.dot(&r1);
+    r3 -= r2 * r3.dot(&r2);
+    r3.normalize();
+
+    [r1, r2, r3]
+}
+
+//---------------------------------------------------------------------------
+// orthonormal_basis
+//
+// Turn three basis vectors into an orthonormal, right-way-round basis,
+// using Gram-Schmidt.  Earlier vectors keep their direction and later ones
+// are adjusted to fit.  Vectors that are zero, or that lie in the span of
+// the earlier ones, are replaced with a perpendicular direction.  Used by
+// decompose.
+fn orthonormal_basis(v: [Vector3; 3]) -> [Vector3; 3] {
+    const EPSILON: f32 = 0.000001;
+
+    let mut basis: [Option<Vector3>; 3] = [None; 3];
+    for i in 0..3 {
+        let mut b = v[i];
+        for prev in basis.iter().flatten() {
+            b -= prev * b.dot(prev);
+        }
+        let mag = b.magnitude();
+        if mag > EPSILON * v[i].magnitude().max(1.0) {
+            basis[i] = Some(b / mag);
+        }
+    }
+
+    // Fill in the missing directions.  For the basis to be right-way-round,
+    // each vector is the cross product of the next two, wrapping around
+    match basis.iter().filter(|b| b.is_some()).count() {
+        0 => {
+            return [
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector3::new(0.0, 0.0, 1.0),
+            ];
+        }
+        1 => {
+            let i = basis.iter().position(|b| b.is_some()).unwrap();
+            let n = basis[i].unwrap();
+
+            // Start from the cardinal axis that is least parallel to n
+            let axes = [
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector3::new(0.0, 0.0, 1.0),
+            ];
+            let mut a = axes[0];
+            for axis in axes.iter() {
+                if n.dot(axis).abs() < n.dot(&a).abs() {
+                    a = *axis;
+                }
+            }
+            let j = (i + 1) % 3;
+            let p = (a - n * n.dot(&a)).normalized();
+            basis[j] = Some(p);
+            basis[(i + 2) % 3] = Some(n.cross(&p));
+        }
+        2 => {
+            let k = basis.iter().position(|b| b.is_none()).unwrap();
+            let a = basis[(k + 1) % 3].unwrap();
+            let b = basis[(k + 2) % 3].unwrap();
+            basis[k] = Some(a.cross(&b));
+        }
+        _ => {}
+    }
+
+    [basis[0].unwrap(), basis[1].unwrap(), basis[2].unwrap()]
+}
+
 //---------------------------------------------------------------------------
 // inverse
 //
 // Compute the inverse of a matrix.  We use the classical adjoint divided
 // by the determinant method.
 //
+// Returns None if the matrix is singular (or very nearly so), such as
+// a projection or a scale by zero on some axis.
+//
 // See 9.2.1 for more info.
-pub fn inverse(m: &Matrix4x3) -> Matrix4x3 {
+pub fn inverse(m: &Matrix4x3) -> Option<Matrix4x3> {
     // Compute the determinant
     let det = determinant(m);
 
     // If we're singular, then the determinant is zero and there's
     // no inverse
-    assert!((det).abs() > 0.000001);
+    if det.abs() <= 0.000001 {
+        return None;
+    }
 
     // Compute one over the determinant, so we divide once and
     // can *multiply* per element
@@ -829,7 +1576,7 @@ pub fn inverse(m: &Matrix4x3) -> Matrix4x3 {
     r.tz = -(m.tx * r.m13 + m.ty * r.m23 + m.tz * r.m33);
 
     // Return it.
-    r
+    Some(r)
 }
 
 //---------------------------------------------------------------------------
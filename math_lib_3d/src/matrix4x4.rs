@@ -0,0 +1,241 @@
+#![allow(dead_code)]
+
+use crate::matrix4x3::Matrix4x3;
+use crate::utils::fovToZoom;
+use crate::vector3::Vector3;
+use std::ops;
+
+/////////////////////////////////////////////////////////////////////////////
+//
+// Notes:
+//
+// Matrix4x4 is used only for the clip (projection) matrix.  A 4x3 matrix
+// always has a rightmost column of [ 0 0 0 1 ], so it can never produce a
+// w other than 1, which is exactly what perspective projection needs.
+// For everything else, use Matrix4x3.
+//
+// We use the same row vector conventions as Matrix4x3:
+//
+//                 | m11 m12 m13 m14 |
+//     [ x y z 1 ] | m21 m22 m23 m24 | = [ x' y' z' w' ]
+//                 | m31 m32 m33 m34 |
+//                 | m41 m42 m43 m44 |
+//
+// Camera space is left-handed, with +z forward.  After the divide by w,
+// points inside the view frustum land in the canonical view volume -1...1
+// on all three axes, with the near plane mapping to z = -1 and the far
+// plane to z = +1.
+//
+// See 15.3.4 for more info.
+//
+/////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix4x4 {
+    pub m11: f32,
+    pub m12: f32,
+    pub m13: f32,
+    pub m14: f32,
+    pub m21: f32,
+    pub m22: f32,
+    pub m23: f32,
+    pub m24: f32,
+    pub m31: f32,
+    pub m32: f32,
+    pub m33: f32,
+    pub m34: f32,
+    pub m41: f32,
+    pub m42: f32,
+    pub m43: f32,
+    pub m44: f32,
+}
+
+impl Matrix4x4 {
+    pub fn identity() -> Matrix4x4 {
+        Matrix4x4 {
+            m11: 1.0,
+            m12: 0.0,
+            m13: 0.0,
+            m14: 0.0,
+            m21: 0.0,
+            m22: 1.0,
+            m23: 0.0,
+            m24: 0.0,
+            m31: 0.0,
+            m32: 0.0,
+            m33: 1.0,
+            m34: 0.0,
+            m41: 0.0,
+            m42: 0.0,
+            m43: 0.0,
+            m44: 1.0,
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // perspective
+    //
+    // Setup a perspective clip matrix.  fov is the horizontal field of view,
+    // in radians.  aspect is the width / height of the output window, and
+    // is used to compute the vertical zoom assuming square pixels.  near
+    // and far are the distances to the clipping planes, and must satisfy
+    // 0 < near < far.
+    //
+    // See 15.2.4 and 15.3.4 for more info.
+    pub fn perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Matrix4x4 {
+        assert!(near > 0.0 && far > near);
+
+        let zoom_x = fovToZoom(fov);
+        let zoom_y = zoom_x * aspect;
+
+        Matrix4x4 {
+            m11: zoom_x,
+            m12: 0.0,
+            m13: 0.0,
+            m14: 0.0,
+            m21: 0.0,
+            m22: zoom_y,
+            m23: 0.0,
+            m24: 0.0,
+            m31: 0.0,
+            m32: 0.0,
+            m33: (far + near) / (far - near),
+            m34: 1.0,
+            m41: 0.0,
+            m42: 0.0,
+            m43: 2.0 * near * far / (near - far),
+            m44: 0.0,
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // orthographic
+    //
+    // Setup an orthographic (parallel) clip matrix.  width and height are the
+    // size of the view volume in camera space, centered on the z axis.
+    // near and far are the distances to the clipping planes.  w is always
+    // 1, so no divide is needed.
+    pub fn orthographic(width: f32, height: f32, near: f32, far: f32) -> Matrix4x4 {
+        assert!(width > 0.0 && height > 0.0 && far > near);
+
+        Matrix4x4 {
+            m11: 2.0 / width,
+            m12: 0.0,
+            m13: 0.0,
+            m14: 0.0,
+            m21: 0.0,
+            m22: 2.0 / height,
+            m23: 0.0,
+            m24: 0.0,
+            m31: 0.0,
+            m32: 0.0,
+            m33: 2.0 / (far - near),
+            m34: 0.0,
+            m41: 0.0,
+            m42: 0.0,
+            m43: -(far + near) / (far - near),
+            m44: 1.0,
+        }
+    }
+
+    //---------------------------------------------------------------------------
+    // transform
+    //
+    // Transform a point, with an assumed w of 1.  Returns the homogeneous
+    // clip space result [ x y z w ], before the divide by w.
+    pub fn transform(&self, p: &Vector3) -> [f32; 4] {
+        [
+            p.x * self.m11 + p.y * self.m21 + p.z * self.m31 + self.m41,
+            p.x * self.m12 + p.y * self.m22 + p.z * self.m32 + self.m42,
+            p.x * self.m13 + p.y * self.m23 + p.z * self.m33 + self.m43,
+            p.x * self.m14 + p.y * self.m24 + p.z * self.m34 + self.m44,
+        ]
+    }
+
+    //---------------------------------------------------------------------------
+    // project_point
+    //
+    // Transform a point and perform the divide by w.  For a point on the
+    // camera plane (w = 0) there is no meaningful projection, and the
+    // result will contain infinite or NaN components.  Clip before
+    // projecting.
+    pub fn project_point(&self, p: &Vector3) -> Vector3 {
+        let [x, y, z, w] = self.transform(p);
+        Vector3::new(x / w, y / w, z / w)
+    }
+}
+
+//---------------------------------------------------------------------------
+// From<&Matrix4x3>
+//
+// Promote a 4x3 matrix to 4x4 by filling in the assumed rightmost column
+// of [ 0 0 0 1 ]
+impl From<&Matrix4x3> for Matrix4x4 {
+    fn from(m: &Matrix4x3) -> Self {
+        Matrix4x4 {
+            m11: m.m11,
+            m12: m.m12,
+            m13: m.m13,
+            m14: 0.0,
+            m21: m.m21,
+            m22: m.m22,
+            m23: m.m23,
+            m24: 0.0,
+            m31: m.m31,
+            m32: m.m32,
+            m33: m.m33,
+            m34: 0.0,
+            m41: m.tx,
+            m42: m.ty,
+            m43: m.tz,
+            m44: 1.0,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------
+// Matrix4x4 * Matrix4x4
+//
+// Matrix concatenation.  See 7.1.6
+impl ops::Mul<&Matrix4x4> for &Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn mul(self, b: &Matrix4x4) -> Self::Output {
+        let a = self;
+        Matrix4x4 {
+            m11: a.m11 * b.m11 + a.m12 * b.m21 + a.m13 * b.m31 + a.m14 * b.m41,
+            m12: a.m11 * b.m12 + a.m12 * b.m22 + a.m13 * b.m32 + a.m14 * b.m42,
+            m13: a.m11 * b.m13 + a.m12 * b.m23 + a.m13 * b.m33 + a.m14 * b.m43,
+            m14: a.m11 * b.m14 + a.m12 * b.m24 + a.m13 * b.m34 + a.m14 * b.m44,
+
+            m21: a.m21 * b.m11 + a.m22 * b.m21 + a.m23 * b.m31 + a.m24 * b.m41,
+            m22: a.m21 * b.m12 + a.m22 * b.m22 + a.m23 * b.m32 + a.m24 * b.m42,
+            m23: a.m21 * b.m13 + a.m22 * b.m23 + a.m23 * b.m33 + a.m24 * b.m43,
+            m24: a.m21 * b.m14 + a.m22 * b.m24 + a.m23 * b.m34 + a.m24 * b.m44,
+
+            m31: a.m31 * b.m11 + a.m32 * b.m21 + a.m33 * b.m31 + a.m34 * b.m41,
+            m32: a.m31 * b.m12 + a.m32 * b.m22 + a.m33 * b.m32 + a.m34 * b.m42,
+            m33: a.m31 * b.m13 + a.m32 * b.m23 + a.m33 * b.m33 + a.m34 * b.m43,
+            m34: a.m31 * b.m14 + a.m32 * b.m24 + a.m33 * b.m34 + a.m34 * b.m44,
+
+            m41: a.m41 * b.m11 + a.m42 * b.m21 + a.m43 * b.m31 + a.m44 * b.m41,
+            m42: a.m41 * b.m12 + a.m42 * b.m22 + a.m43 * b.m32 + a.m44 * b.m42,
+            m43: a.m41 * b.m13 + a.m42 * b.m23 + a.m43 * b.m33 + a.m44 * b.m43,
+            m44: a.m41 * b.m14 + a.m42 * b.m24 + a.m43 * b.m34 + a.m44 * b.m44,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------
+// Matrix4x3 * Matrix4x4
+//
+// Concatenate a 4x3 transform with a clip matrix, such as model -> camera
+// followed by camera -> clip.  The 4x3 matrix is promoted first.
+impl ops::Mul<&Matrix4x4> for &Matrix4x3 {
+    type Output = Matrix4x4;
+
+    fn mul(self, b: &Matrix4x4) -> Self::Output {
+        &Matrix4x4::from(self) * b
+    }
+}
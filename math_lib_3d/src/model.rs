@@ -151,6 +151,14 @@ impl Model {
             return;
         }
 
+        // Make sure the indices are sane before we start slicing it up
+
+        debug_assert!(
+            mesh.index_check().is_ok(),
+            "EditTriMesh failed index check:\n{}",
+            mesh.index_check().unwrap_err()
+        );
+
         // Extract the part meshes
 
         let mut partMeshes: Vec<EditTriMesh> = Vec::with_capacity(mesh.pList.len());
@@ -0,0 +1,90 @@
+use crate::aabb3::AABB3;
+use crate::vector3::Vector3;
+
+// Implement a plane, using the implicit form
+//
+//     p . n = d
+//
+// where n is the unit normal.  The front side of the plane is the side the
+// normal points toward.  This is the same (n, d) pair that
+// AABB3::classify_plane and AABB3::intersect_plane take.  See 12.5
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plane {
+    pub normal: Vector3,
+    pub d: f32,
+}
+
+impl Plane {
+    // Construct the plane through a point, with the given normal.  The
+    // normal doesn't need to be unit length, but must not be zero
+    pub fn from_point_normal(p: &Vector3, normal: &Vector3) -> Plane {
+        let n = normal.normalized();
+        Plane {
+            normal: n,
+            d: p.dot(&n),
+        }
+    }
+
+    // Construct the plane containing a triangle.  The vertices are listed
+    // clockwise when viewed from the front side, as for EditTriMesh.  The
+    // triangle must not be degenerate
+    pub fn from_triangle(a: &Vector3, b: &Vector3, c: &Vector3) -> Plane {
+        // Compute clockwise edge vectors, the same as computeOneTriNormal
+        let e1 = c - b;
+        let e2 = a - c;
+
+        Plane::from_point_normal(a, &e1.cross(&e2))
+    }
+
+    // Signed distance from the plane to a point.  Positive is in front,
+    // negative is behind
+    pub fn distance_to(&self, p: &Vector3) -> f32 {
+        p.dot(&self.normal) - self.d
+    }
+
+    // Classify a point against the plane.  Returns:
+    //
+    // <0	Point is on the BACK side of the plane
+    // >0	Point is on the FRONT side of the plane
+    // 0	Point is on the plane, within epsilon
+    pub fn classify_point(&self, p: &Vector3, epsilon: f32) -> i32 {
+        let distance = self.distance_to(p);
+        if distance > epsilon {
+            1
+        } else if distance < -epsilon {
+            -1
+        } else {
+            0
+        }
+    }
+
+    // Classify a box against the plane, the same way as classify_point().
+    // Returns 0 if the box straddles the plane.  See AABB3::classify_plane
+    pub fn classify_aabb(&self, b: &AABB3) -> i32 {
+        b.classify_plane(&self.normal, self.d)
+    }
+
+    // Parametric intersection with a ray.  Returns t such that
+    // ray_org + ray_dir * t is on the plane, or None if the ray is parallel
+    // to the plane or points away from it.  Either side of the plane can
+    // be hit.  If ray_dir is the full length of a segment, check t <= 1
+    pub fn ray_intersect(&self, ray_org: &Vector3, ray_dir: &Vector3) -> Option<f32> {
+        let dot = ray_dir.dot(&self.normal);
+
+        // Parallel to the plane?
+        if dot.abs() < 0.000001 {
+            return None;
+        }
+
+        let t = (self.d - ray_org.dot(&self.normal)) / dot;
+
+        // Behind the start of the ray?
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(t)
+    }
+}
@@ -1,11 +1,14 @@
 #![allow(dead_code)]
 
 use crate::euler_angles::EulerAngles;
+use crate::rotation_matrix::RotationMatrix;
 use crate::utils::{atan2, safe_acos};
 use crate::vector3::Vector3;
+use std::f32::consts::PI;
 use std::ops;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Quaternion {
     pub x: f32,
     pub y: f32,
@@ -77,6 +80,49 @@ impl Quaternion {
         self.z = axis.z * sin_theta_over_2;
     }
 
+    // Construct a quaternion to rotate about an arbitrary axis by theta.
+    // The axis must be normalized, as in set_to_rotate_about_axis()
+    pub fn from_axis_angle(axis: &Vector3, theta: f32) -> Quaternion {
+        let mut q = Quaternion::identity();
+        q.set_to_rotate_about_axis(*axis, theta);
+        q
+    }
+
+    // Construct the shortest arc rotation that takes the direction from
+    // onto the direction to.  Neither vector needs to be normalized, but
+    // both must be nonzero.  The axis is from x to, and the angle is the
+    // angle between them.  If the vectors point in opposite directions,
+    // any perpendicular axis works, so we just pick one
+    pub fn from_to_rotation(from: &Vector3, to: &Vector3) -> Quaternion {
+        let f = from.normalized();
+        let t = to.normalized();
+        let cos_theta = f.dot(&t);
+
+        if cos_theta < -0.9999 {
+            // Antiparallel.  Rotate 180 degrees about any axis
+            // perpendicular to from.  Try the x axis first, and if from
+            // is too close to it, use the y axis instead
+            let mut axis = f.cross(&Vector3::new(1.0, 0.0, 0.0));
+            if axis.dot(&axis) < 0.0001 {
+                axis = f.cross(&Vector3::new(0.0, 1.0, 0.0));
+            }
+            return Quaternion::from_axis_angle(&axis.normalized(), PI);
+        }
+
+        // The cross product has length sin(theta), and 1 + cos(theta) is
+        // proportional to cos(theta/2), so normalizing [ 1 + cos  sin n ]
+        // gives the half angle without any trig
+        let axis = f.cross(&t);
+
+        Quaternion {
+            w: 1.0 + cos_theta,
+            x: axis.x,
+            y: axis.y,
+            z: axis.z,
+        }
+        .normalized()
+    }
+
     // Setup the quaternion to perform an object->inertial rotation, given the
     // orientation in Euler angle format
     pub fn set_to_rotate_object_to_inertial(&mut self, orientation: EulerAngles) {
@@ -103,6 +149,37 @@ impl Quaternion {
         self.z = sin_heading * sin_pitch * cos_bank - cos_heading * cos_pitch * sin_bank;
     }
 
+    // Quaternion::toRotationMatrix
+    //
+    // Return the rotation matrix for this quaternion, which is assumed to
+    // perform an inertial->object rotation.  Same as
+    // RotationMatrix::from_inertial_to_object_quaternion
+    pub fn to_rotation_matrix(&self) -> RotationMatrix {
+        RotationMatrix::from_inertial_to_object_quaternion(self)
+    }
+
+    // Quaternion::toEulerInertialToObject
+    //
+    // Return the Euler angles for this quaternion, which is assumed to
+    // perform an inertial->object rotation.  Same as
+    // EulerAngles::from_inertial_to_object_quaternion
+    pub fn to_euler_inertial_to_object(&self) -> EulerAngles {
+        EulerAngles::from_inertial_to_object_quaternion(self)
+    }
+
+    // Quaternion::magnitude
+    //
+    // Return the magnitude (norm) of the quaternion.  A quaternion that
+    // represents a rotation has unit magnitude.  See 10.4.6
+    pub fn magnitude(&self) -> f32 {
+        self.magnitude_squared().sqrt()
+    }
+
+    // Return the squared magnitude, which avoids the square root
+    pub fn magnitude_squared(&self) -> f32 {
+        self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
     // Quaternion::normalize
     //
     // "Normalize" a quaternion.  Note that normally, quaternions
@@ -112,10 +189,13 @@ impl Quaternion {
     // This function is provided primarily to combat floating point "error
     // creep," which can occur when many successive quaternion operations
     // are applied.
-    pub fn normalize(&mut self) {
+    //
+    // Returns the magnitude before normalizing.  A zero quaternion can't be
+    // normalized, so it is slammed to the identity, and 0.0 is returned.
+    pub fn normalize(&mut self) -> f32 {
         // Compute magnitude of the quaternion
 
-        let mag = (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        let mag = self.magnitude();
 
         // Check for bogus length, to protect against divide by zero
         if mag > 0.0 {
@@ -126,16 +206,19 @@ impl Quaternion {
             self.y *= one_over_mag;
             self.z *= one_over_mag;
         } else {
-            // Houston, we have a problem
-            assert!(false);
-
-            // In a release build, just slam it to something
-            // Quaternion::identity()
-            self.x = 0.0;
-            self.y = 0.0;
-            self.z = 0.0;
-            self.w = 1.0;
+            // Houston, we have a problem.  Just slam it to something
+            *self = Quaternion::identity();
         }
+
+        mag
+    }
+
+    // Return a normalized copy of the quaternion, leaving this one
+    // unchanged.  A zero quaternion gives the identity, as in normalize()
+    pub fn normalized(&self) -> Quaternion {
+        let mut result = *self;
+        result.normalize();
+        result
     }
 
     // Quaternion::getRotationAngle
@@ -181,6 +264,154 @@ impl Quaternion {
             z: self.z * one_over_sin_theta_over2,
         }
     }
+
+    // Quaternion::toAxisAngle
+    // Return the rotation axis and angle together, the inverse of
+    // from_axis_angle().  For the identity the axis is arbitrary, and the
+    // angle is zero
+    pub fn to_axis_angle(&self) -> (Vector3, f32) {
+        (self.get_rotation_axis(), self.get_rotation_angle())
+    }
+
+    // Quaternion::log
+    //
+    // Quaternion logarithm.  A unit quaternion can be written as
+    //
+    //     q = [ cos(alpha)  n sin(alpha) ]
+    //
+    // where n is the unit axis and alpha is the half angle.  The log is the
+    // pure vector quaternion [ 0  alpha n ].  See 10.4.11
+    pub fn log(&self) -> Quaternion {
+        // Extract the half angle.  Use the "safe" acos in case error creep
+        // has pushed w slightly out of range
+        let alpha = safe_acos(self.w);
+        let sin_alpha = alpha.sin();
+
+        // For a (near) identity quaternion the axis is meaningless, and
+        // alpha / sin(alpha) approaches 1, so just keep the vector part
+        let k = if sin_alpha.abs() > 0.0001 {
+            alpha / sin_alpha
+        } else {
+            1.0
+        };
+
+        Quaternion {
+            w: 0.0,
+            x: self.x * k,
+            y: self.y * k,
+            z: self.z * k,
+        }
+    }
+
+    // Quaternion::exp
+    //
+    // Quaternion exponential, the inverse of log().  The w component of the
+    // input is ignored, and the vector part [ alpha n ] is turned back into
+    // [ cos(alpha)  n sin(alpha) ].  See 10.4.11
+    pub fn exp(&self) -> Quaternion {
+        let alpha = (self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+
+        // sin(alpha) / alpha approaches 1 as alpha goes to zero
+        let k = if alpha > 0.0001 {
+            alpha.sin() / alpha
+        } else {
+            1.0
+        };
+
+        Quaternion {
+            w: alpha.cos(),
+            x: self.x * k,
+            y: self.y * k,
+            z: self.z * k,
+        }
+    }
+
+    // Quaternion::isIdentity
+    //
+    // Return true if this quaternion represents no rotation, within
+    // epsilon.  Both [ 1 0 ] and [ -1 0 ] count, see 10.4.3
+    pub fn is_identity(&self, epsilon: f32) -> bool {
+        self.approx_eq(&Quaternion::identity(), epsilon)
+    }
+
+    // Quaternion::canonical
+    //
+    // Return the unique representative of this orientation with w >= 0.
+    // q and -q describe the same orientation, so flipping the sign when w
+    // is negative gives a single quaternion for each orientation, which
+    // can be compared, sorted, or hashed.  If w is exactly zero, the first
+    // nonzero component of the vector part is made positive instead
+    pub fn canonical(&self) -> Quaternion {
+        let flip = if self.w != 0.0 {
+            self.w < 0.0
+        } else if self.x != 0.0 {
+            self.x < 0.0
+        } else if self.y != 0.0 {
+            self.y < 0.0
+        } else {
+            self.z < 0.0
+        };
+
+        if flip {
+            -self
+        } else {
+            *self
+        }
+    }
+
+    // Quaternion::concatenate
+    //
+    // Concatenate two angular displacements: apply self first, then `then`.
+    // This is the same as self * then, since our multiplication reads left
+    // to right in the order the rotations are applied, which is the reverse
+    // of the standard definition.  See 10.4.8
+    pub fn concatenate(&self, then: &Quaternion) -> Quaternion {
+        self * then
+    }
+
+    // Quaternion::approxEq
+    //
+    // Compare two quaternions, component by component, within epsilon.
+    // q and -q represent the same orientation (see 10.4.3), so the other
+    // quaternion is considered equal if either it or its negation matches.
+    pub fn approx_eq(&self, other: &Quaternion, epsilon: f32) -> bool {
+        let same = (self.w - other.w).abs() <= epsilon
+            && (self.x - other.x).abs() <= epsilon
+            && (self.y - other.y).abs() <= epsilon
+            && (self.z - other.z).abs() <= epsilon;
+
+        let negated = (self.w + other.w).abs() <= epsilon
+            && (self.x + other.x).abs() <= epsilon
+            && (self.y + other.y).abs() <= epsilon
+            && (self.z + other.z).abs() <= epsilon;
+
+        same || negated
+    }
+}
+
+// Quaternion::operator -
+//
+// Negate all four components.  The result represents the same angular
+// displacement, going "the long way around."  See 10.4.3
+impl ops::Neg for &Quaternion {
+    type Output = Quaternion;
+
+    fn neg(self) -> Self::Output {
+        Quaternion {
+            w: -self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl ops::Neg for Quaternion {
+    type Output = Quaternion;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
 }
 
 // Quaternion::operator *
@@ -191,10 +422,10 @@ impl Quaternion {
 // applied.  This is backwards from the *standard* definition of
 // quaternion multiplication.  See section 10.4.8 for the rationale
 // behind this deviation from the standard.
-impl ops::Mul<Quaternion> for Quaternion {
+impl ops::Mul<&Quaternion> for &Quaternion {
     type Output = Quaternion;
 
-    fn mul(self, a: Quaternion) -> Self::Output {
+    fn mul(self, a: &Quaternion) -> Self::Output {
         Quaternion {
             w: self.w * a.w - self.x * a.x - self.y * a.y - self.z * a.z,
             x: self.w * a.x + self.x * a.w + self.z * a.y - self.y * a.z,
@@ -204,14 +435,21 @@ impl ops::Mul<Quaternion> for Quaternion {
     }
 }
 
+impl ops::Mul<Quaternion> for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, a: Quaternion) -> Self::Output {
+        &self * &a
+    }
+}
+
 // Quaternion::operator *=
 // Combined cross product and assignment, as per C++ convention
 impl ops::MulAssign<Quaternion> for Quaternion {
     fn mul_assign(&mut self, a: Quaternion) {
-        self.w = self.w * a.w - self.x * a.x - self.y * a.y - self.z * a.z;
-        self.x = self.w * a.x + self.x * a.w + self.z * a.y - self.y * a.z;
-        self.y = self.w * a.y + self.y * a.w + self.x * a.z - self.z * a.x;
-        self.w = self.w * a.z + self.z * a.w + self.y * a.x - self.x * a.y;
+        // Every output reads all four of our inputs, so compute the
+        // whole product before overwriting anything
+        *self = *self * a;
     }
 }
 
@@ -228,21 +466,73 @@ pub fn dot_product(a: &Quaternion, b: &Quaternion) -> f32 {
 }
 
 //---------------------------------------------------------------------------
-// slerp
+// arc_weights
 //
-// Spherical linear interpolation.
+// Compute the slerp blend weights for two unit quaternions whose dot
+// product is cos_omega, following the arc between them as given.  Used by
+// slerp_weights and slerp_no_invert.  The quaternions must not be almost
+// exactly opposite, since then there is no unique arc between them.
+
+fn arc_weights(cos_omega: f32, t: f32) -> (f32, f32) {
+    // We should have two unit quaternions, so dot should be <= 1.0
+
+    assert!(cos_omega.abs() < 1.1);
+    debug_assert!(cos_omega > -0.9999);
+
+    // Compute interpolation fraction, checking for quaternions
+    // almost exactly the same
+
+    if cos_omega > 0.9999 {
+        // Very close - just use linear interpolation,
+        // which will protect againt a divide by zero
+
+        return (1.0 - t, t);
+    }
+
+    // Compute the sin of the angle using the
+    // trig identity sin^2(omega) + cos^2(omega) = 1
+
+    let sin_omega = (1.0 - cos_omega * cos_omega).sqrt();
+
+    // Compute the angle from its sin and cosine
+
+    let omega = atan2(sin_omega, cos_omega);
+
+    // Compute inverse of denominator, so we only have
+    // to divide once
+
+    let one_over_sin_omega = 1.0 / sin_omega;
+
+    // Compute interpolation parameters
+
+    (
+        ((1.0 - t) * omega).sin() * one_over_sin_omega,
+        (t * omega).sin() * one_over_sin_omega,
+    )
+}
+
+//---------------------------------------------------------------------------
+// slerpWeights
+//
+// Compute the blend weights (k0, k1) used by slerp, so that
+//
+//     slerp(q0, q1, t) = k0 q0 + k1 q1
+//
+// This is useful for blending other data associated with the two
+// orientations using the same weights.  If the short arc goes to -q1, k1
+// is negated, so the weights can always be applied to q1 as given.
 //
 // See 10.4.13
 
-pub fn slerp(q0: &Quaternion, q1: &Quaternion, t: f32) -> Quaternion {
+pub fn slerp_weights(q0: &Quaternion, q1: &Quaternion, t: f32) -> (f32, f32) {
     // Check for out-of range parameter and return edge points if so
 
     if t <= 0.0 {
-        return q0.clone();
+        return (1.0, 0.0);
     }
 
     if t >= 1.0 {
-        return q1.clone();
+        return (0.0, 1.0);
     }
 
     // Compute "cosine of angle between quaternions" using dot product
@@ -254,66 +544,157 @@ pub fn slerp(q0: &Quaternion, q1: &Quaternion, t: f32) -> Quaternion {
     // different slerp.  We chose q or -q to rotate using
     // the acute angle.
 
-    let mut q1w = q1.w;
-    let mut q1x = q1.x;
-    let mut q1y = q1.y;
-    let mut q1z = q1.z;
+    let mut q1_sign = 1.0;
 
     if cos_omega < 0.0 {
-        q1w = -q1w;
-        q1x = -q1x;
-        q1y = -q1y;
-        q1z = -q1z;
+        q1_sign = -1.0;
         cos_omega = -cos_omega;
     }
 
-    // We should have two unit quaternions, so dot should be <= 1.0
-
-    assert!(cos_omega < 1.1);
+    let (k0, k1) = arc_weights(cos_omega, t);
 
-    // Compute interpolation fraction, checking for quaternions
-    // almost exactly the same
+    (k0, k1 * q1_sign)
+}
 
-    let k0: f32;
-    let k1: f32;
+//---------------------------------------------------------------------------
+// slerp
+//
+// Spherical linear interpolation.
+//
+// See 10.4.13
 
-    if cos_omega > 0.9999 {
-        // Very close - just use linear interpolation,
-        // which will protect againt a divide by zero
+pub fn slerp(q0: &Quaternion, q1: &Quaternion, t: f32) -> Quaternion {
+    // Check for out-of range parameter and return edge points if so
 
-        k0 = 1.0 - t;
-        k1 = t;
-    } else {
-        // Compute the sin of the angle using the
-        // trig identity sin^2(omega) + cos^2(omega) = 1
+    if t <= 0.0 {
+        return *q0;
+    }
 
-        let sin_omega = (1.0 - cos_omega * cos_omega).sqrt();
+    if t >= 1.0 {
+        return *q1;
+    }
 
-        // Compute the angle from its sin and cosine
+    let (k0, k1) = slerp_weights(q0, q1, t);
 
-        let omega = atan2(sin_omega, cos_omega);
+    // Interpolate
 
-        // Compute inverse of denominator, so we only have
-        // to divide once
+    Quaternion {
+        x: k0 * q0.x + k1 * q1.x,
+        y: k0 * q0.y + k1 * q1.y,
+        z: k0 * q0.z + k1 * q1.z,
+        w: k0 * q0.w + k1 * q1.w,
+    }
+}
 
-        let one_over_sin_omega = 1.0 / sin_omega;
+//---------------------------------------------------------------------------
+// slerp_no_invert
+//
+// Same as slerp, but always interpolates from q0 to q1 as given, even if
+// the arc to -q1 is shorter.  squad needs this: choosing the short arc
+// inside the interpolation can switch arcs partway through a segment, and
+// the curve jumps.  Quaternions that are almost exactly opposite have no
+// unique arc between them, so we take the half turn through a quaternion
+// perpendicular to q0.
+
+pub fn slerp_no_invert(q0: &Quaternion, q1: &Quaternion, t: f32) -> Quaternion {
+    if t <= 0.0 {
+        return *q0;
+    }
 
-        // Compute interpolation parameters
+    if t >= 1.0 {
+        return *q1;
+    }
 
-        k0 = ((1.0 - t) * omega).sin() * one_over_sin_omega;
-        k1 = (t * omega).sin() * one_over_sin_omega;
+    let cos_omega = dot_product(q0, q1);
+
+    if cos_omega < -0.9999 {
+        // Blending q0 with (nearly) -q0 would pass through zero, which is
+        // not a rotation.  Every great circle through q0 also passes through
+        // -q0, so pick one using a quaternion perpendicular to q0 in 4D
+
+        let p = Quaternion {
+            w: -q0.z,
+            x: -q0.y,
+            y: q0.x,
+            z: q0.w,
+        };
+        let (sin, cos) = (t * PI).sin_cos();
+
+        return Quaternion {
+            x: cos * q0.x + sin * p.x,
+            y: cos * q0.y + sin * p.y,
+            z: cos * q0.z + sin * p.z,
+            w: cos * q0.w + sin * p.w,
+        };
     }
 
-    // Interpolate
+    let (k0, k1) = arc_weights(cos_omega, t);
 
     Quaternion {
-        x: k0 * q0.x + k1 * q1x,
-        y: k0 * q0.y + k1 * q1y,
-        z: k0 * q0.z + k1 * q1z,
-        w: k0 * q0.w + k1 * q1w,
+        x: k0 * q0.x + k1 * q1.x,
+        y: k0 * q0.y + k1 * q1.y,
+        z: k0 * q0.z + k1 * q1.z,
+        w: k0 * q0.w + k1 * q1.w,
     }
 }
 
+//---------------------------------------------------------------------------
+// intermediate
+//
+// Compute the inner control point for squad at key q_cur, given its
+// neighbors on either side:
+//
+//     s = q_cur exp( -(log(q_cur^-1 q_next) + log(q_cur^-1 q_prev)) / 4 )
+//
+// At the first and last keys, pass the key itself as the missing
+// neighbor.  Neighboring keys should be in the same "hemisphere" (positive
+// dot product) - negate a key if necessary before building the spline.
+
+pub fn intermediate(q_prev: &Quaternion, q_cur: &Quaternion, q_next: &Quaternion) -> Quaternion {
+    let to_next = difference(q_cur, q_next).log();
+    let to_prev = difference(q_cur, q_prev).log();
+
+    let e = Quaternion {
+        w: 0.0,
+        x: -(to_next.x + to_prev.x) * 0.25,
+        y: -(to_next.y + to_prev.y) * 0.25,
+        z: -(to_next.z + to_prev.z) * 0.25,
+    };
+
+    q_cur * &e.exp()
+}
+
+//---------------------------------------------------------------------------
+// squad
+//
+// Spherical quadrangle interpolation between q1 and q2, using q0 and q3
+// (the keys before and after) to shape the curve.  Unlike a chain of
+// slerps, a spline of squad segments has a continuous angular velocity
+// at the keys.  t is in the range 0...1
+//
+//     squad = slerp( slerp(q1, q2, t), slerp(s1, s2, t), 2t(1-t) )
+//
+// where s1 and s2 are the control points from intermediate().  All three
+// slerps are done with slerp_no_invert, so the curve can't flip between
+// arcs partway through.
+
+pub fn squad(
+    q0: &Quaternion,
+    q1: &Quaternion,
+    q2: &Quaternion,
+    q3: &Quaternion,
+    t: f32,
+) -> Quaternion {
+    let s1 = intermediate(q0, q1, q2);
+    let s2 = intermediate(q1, q2, q3);
+
+    slerp_no_invert(
+        &slerp_no_invert(q1, q2, t),
+        &slerp_no_invert(&s1, &s2, t),
+        2.0 * t * (1.0 - t),
+    )
+}
+
 //---------------------------------------------------------------------------
 // conjugate
 //
@@ -331,10 +712,26 @@ pub fn conjugate(q: &Quaternion) -> Quaternion {
     }
 }
 
+//---------------------------------------------------------------------------
+// difference
+//
+// Compute the angular displacement that rotates from one orientation to
+// another.  We want d such that from * d = to, which, with our left to
+// right multiplication order, is
+//
+//     d = conjugate(from) * to
+//
+// Both inputs should be unit quaternions.  See 10.4.9
+
+pub fn difference(from: &Quaternion, to: &Quaternion) -> Quaternion {
+    &conjugate(from) * to
+}
+
 //---------------------------------------------------------------------------
 // pow
 //
-// Quaternion exponentiation.
+// Quaternion exponentiation, computed as exp(exponent * log(q)).  This
+// scales the angle of rotation, keeping the same axis.  See 10.4.12
 pub fn pow(q: Quaternion, exponent: f32) -> Quaternion {
     // Check for the case of an identity quaternion.
     // This will protect against divide by zero
@@ -343,22 +740,10 @@ pub fn pow(q: Quaternion, exponent: f32) -> Quaternion {
         return q;
     }
 
-    // Extract the half angle alpha (alpha = theta/2)
-    let alpha = (q.w).acos();
-
-    // Compute new alpha value
+    let mut l = q.log();
+    l.x *= exponent;
+    l.y *= exponent;
+    l.z *= exponent;
 
-    let new_alpha = alpha * exponent;
-
-    // Compute new w value
-
-    let mult = new_alpha.sin() / alpha.sin();
-
-    Quaternion {
-        w: new_alpha.cos(),
-        // Compute new xyz values
-        x: q.x * mult,
-        y: q.y * mult,
-        z: q.z * mult,
-    }
+    l.exp()
 }
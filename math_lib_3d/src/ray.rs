@@ -0,0 +1,40 @@
+use crate::aabb3::AABB3;
+use crate::vector3::Vector3;
+
+// Implement a ray in parametric form
+//
+//     p(t) = origin + direction * t
+//
+// As with AABB3::ray_intersect, direction is the full length of the ray, not
+// a unit vector, so the ray runs from p(0) to p(1).  This lets picking code
+// pass a ray around as a single object.  See 12.2
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ray {
+    pub origin: Vector3,
+    pub direction: Vector3,
+}
+
+impl Ray {
+    pub fn new(origin: Vector3, direction: Vector3) -> Ray {
+        Ray { origin, direction }
+    }
+
+    // Return the point at parametric distance t along the ray
+    pub fn point_at(&self, t: f32) -> Vector3 {
+        self.origin + self.direction * t
+    }
+
+    // Intersect with a box.  Returns the parametric distance in range 0...1
+    // and the surface normal at the hit point, or None if there is no
+    // intersection.  See AABB3::ray_intersect
+    pub fn intersect_aabb(&self, b: &AABB3) -> Option<(f32, Vector3)> {
+        let mut normal = Vector3::zero();
+        let t = b.ray_intersect(&self.origin, &self.direction, Some(&mut normal));
+        if t > 1.0 {
+            return None;
+        }
+        Some((t, normal))
+    }
+}
@@ -45,10 +45,13 @@
 /////////////////////////////////////////////////////////////////////////////
 
 use crate::euler_angles::EulerAngles;
+use crate::matrix4x3::{gram_schmidt, Matrix4x3};
 use crate::quaternion::Quaternion;
-use crate::vector3::Vector3;
+use crate::vector3::{scalar_triple, Vector3};
+use std::fmt;
+use std::ops;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct RotationMatrix {
     pub m11: f32,
     pub m12: f32,
@@ -77,27 +80,10 @@ impl RotationMatrix {
         }
     }
 
-    // Setup the matrix with the specified orientation
+    // Setup the matrix with the specified orientation.  See
+    // from_euler_angles()
     pub fn setup(&mut self, orientation: &EulerAngles) {
-        // Fetch sine and cosine of angles
-
-        let (sin_heading, cos_heading) = orientation.heading.sin_cos();
-        let (sin_pitch, cos_pitch) = orientation.pitch.sin_cos();
-        let (sin_bank, cos_bank) = orientation.bank.sin_cos();
-
-        // Fill in the matrix elements
-
-        self.m11 = cos_heading * cos_bank + sin_heading * sin_pitch * sin_bank;
-        self.m12 = -cos_heading * sin_bank + sin_heading * sin_pitch * cos_bank;
-        self.m13 = sin_heading * cos_pitch;
-
-        self.m21 = sin_bank * cos_pitch;
-        self.m22 = cos_bank * cos_pitch;
-        self.m23 = -sin_pitch;
-
-        self.m31 = -sin_heading * cos_bank + cos_heading * sin_pitch * sin_bank;
-        self.m32 = sin_bank * sin_heading + cos_heading * sin_pitch * cos_bank;
-        self.m33 = cos_heading * cos_pitch;
+        *self = RotationMatrix::from_euler_angles(orientation);
     }
 
     // Setup new matrix with the specified orientation
@@ -125,6 +111,43 @@ impl RotationMatrix {
         }
     }
 
+    // Setup new matrix to rotate about an arbitrary axis through the origin.
+    // The axis must be a unit vector.  inertial_to_object() then rotates a
+    // vector by theta about the axis.  The elements are the linear portion
+    // of Matrix4x3::from_axis_angle.  See 8.2.3
+    pub fn from_axis_angle(axis: &Vector3, theta: f32) -> RotationMatrix {
+        let m = Matrix4x3::from_axis_angle(axis, theta);
+        RotationMatrix {
+            m11: m.m11,
+            m12: m.m12,
+            m13: m.m13,
+
+            m21: m.m21,
+            m22: m.m22,
+            m23: m.m23,
+
+            m31: m.m31,
+            m32: m.m32,
+            m33: m.m33,
+        }
+    }
+
+    // Setup new matrix, given a quaternion that performs an inertial->object
+    // rotation
+    pub fn from_inertial_to_object_quaternion(q: &Quaternion) -> RotationMatrix {
+        let mut m = RotationMatrix::identity();
+        m.set_from_inertial_to_object_quaternion(q);
+        m
+    }
+
+    // Setup new matrix, given a quaternion that performs an object->inertial
+    // rotation
+    pub fn from_object_to_inertial_quaternion(q: &Quaternion) -> RotationMatrix {
+        let mut m = RotationMatrix::identity();
+        m.set_from_object_to_inertial_quaternion(q);
+        m
+    }
+
     // Setup the matrix, given a quaternion that performs an inertial->object
     // rotation
     pub fn set_from_inertial_to_object_quaternion(&mut self, q: &Quaternion) {
@@ -163,6 +186,70 @@ impl RotationMatrix {
         self.m33 = 1.0 - 2.0 * (q.x * q.x + q.y * q.y);
     }
 
+    // Force the matrix back to orthonormal using Gram-Schmidt, to combat
+    // the error creep from successive multiplications.  Each row is made
+    // perpendicular to the rows before it and then normalized.  See 9.3.3
+    pub fn orthonormalize(&mut self) {
+        let [r1, r2, r3] = gram_schmidt([
+            Vector3::new(self.m11, self.m12, self.m13),
+            Vector3::new(self.m21, self.m22, self.m23),
+            Vector3::new(self.m31, self.m32, self.m33),
+        ]);
+
+        self.m11 = r1.x;
+        self.m12 = r1.y;
+        self.m13 = r1.z;
+        self.m21 = r2.x;
+        self.m22 = r2.y;
+        self.m23 = r2.z;
+        self.m31 = r3.x;
+        self.m32 = r3.y;
+        self.m33 = r3.z;
+    }
+
+    // Check that the matrix really is a rotation: the rows are unit length
+    // and mutually perpendicular, and the determinant is +1 rather than -1,
+    // which would be a reflection.  Anything that assumes orthogonality,
+    // such as conversion to Euler angles, will give garbage otherwise.
+    // See 9.3
+    pub fn is_valid(&self, epsilon: f32) -> bool {
+        let r1 = Vector3::new(self.m11, self.m12, self.m13);
+        let r2 = Vector3::new(self.m21, self.m22, self.m23);
+        let r3 = Vector3::new(self.m31, self.m32, self.m33);
+
+        r1.is_unit(epsilon)
+            && r2.is_unit(epsilon)
+            && r3.is_unit(epsilon)
+            && r1.dot(&r2).abs() < epsilon
+            && r1.dot(&r3).abs() < epsilon
+            && r2.dot(&r3).abs() < epsilon
+            && (scalar_triple(&r1, &r2, &r3) - 1.0).abs() < epsilon
+    }
+
+    // Return the transpose of the matrix.  A rotation matrix is orthogonal,
+    // so this is also the inverse.  It swaps the direction of the
+    // transformation: inertial_to_object() on the transpose is the same as
+    // object_to_inertial() on the original.  See 9.3.1
+    pub fn transpose(&self) -> RotationMatrix {
+        RotationMatrix {
+            m11: self.m11,
+            m12: self.m21,
+            m13: self.m31,
+            m21: self.m12,
+            m22: self.m22,
+            m23: self.m32,
+            m31: self.m13,
+            m32: self.m23,
+            m33: self.m33,
+        }
+    }
+
+    // Return the inverse of the matrix, which for a rotation matrix is
+    // just the transpose
+    pub fn inverse(&self) -> RotationMatrix {
+        self.transpose()
+    }
+
     // Rotate a vector from inertial to object space
     pub fn inertial_to_object(&self, v: &Vector3) -> Vector3 {
         // Perform the matrix multiplication in the "standard" way.
@@ -182,4 +269,78 @@ impl RotationMatrix {
             z: self.m31 * v.x + self.m32 * v.y + self.m33 * v.z,
         }
     }
+
+    // Rotate a whole list of vectors (such as vertex normals) from inertial
+    // to object space, in place
+    pub fn inertial_to_object_slice(&self, v: &mut [Vector3]) {
+        for p in v.iter_mut() {
+            *p = self.inertial_to_object(p);
+        }
+    }
+
+    // Rotate a whole list of vectors from object to inertial space, in place
+    pub fn object_to_inertial_slice(&self, v: &mut [Vector3]) {
+        for p in v.iter_mut() {
+            *p = self.object_to_inertial(p);
+        }
+    }
+}
+
+// RotationMatrix * RotationMatrix
+//
+// Concatenate two inertial->object rotations.  As with Matrix4x3 and
+// Quaternion, the order reads left to right: a * b applies a first, then
+// b, so
+//
+//     (a * b).inertial_to_object(v) == b.inertial_to_object(&a.inertial_to_object(v))
+//
+// The result is also orthogonal, aside from floating point error creep.
+// See 7.1.6
+impl ops::Mul for RotationMatrix {
+    type Output = RotationMatrix;
+
+    fn mul(self, b: Self) -> Self::Output {
+        RotationMatrix {
+            m11: self.m11 * b.m11 + self.m12 * b.m21 + self.m13 * b.m31,
+            m12: self.m11 * b.m12 + self.m12 * b.m22 + self.m13 * b.m32,
+            m13: self.m11 * b.m13 + self.m12 * b.m23 + self.m13 * b.m33,
+
+            m21: self.m21 * b.m11 + self.m22 * b.m21 + self.m23 * b.m31,
+            m22: self.m21 * b.m12 + self.m22 * b.m22 + self.m23 * b.m32,
+            m23: self.m21 * b.m13 + self.m22 * b.m23 + self.m23 * b.m33,
+
+            m31: self.m31 * b.m11 + self.m32 * b.m21 + self.m33 * b.m31,
+            m32: self.m31 * b.m12 + self.m32 * b.m22 + self.m33 * b.m32,
+            m33: self.m31 * b.m13 + self.m32 * b.m23 + self.m33 * b.m33,
+        }
+    }
+}
+
+impl ops::Mul<&RotationMatrix> for &RotationMatrix {
+    type Output = RotationMatrix;
+
+    fn mul(self, b: &RotationMatrix) -> Self::Output {
+        *self * *b
+    }
+}
+
+// Print the matrix as a grid of three rows.  The numbers are printed with
+// 4 decimal places, unless a precision is given, as in {:.2}
+impl fmt::Display for RotationMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let p = f.precision().unwrap_or(4);
+        let w = p + 5;
+        let rows = [
+            [self.m11, self.m12, self.m13],
+            [self.m21, self.m22, self.m23],
+            [self.m31, self.m32, self.m33],
+        ];
+        for (i, r) in rows.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "| {:w$.p$} {:w$.p$} {:w$.p$} |", r[0], r[1], r[2])?;
+        }
+        Ok(())
+    }
 }
@@ -300,13 +300,24 @@ pub fn import_s3d(filename: &str) -> Result<EditTriMesh, Error> {
             debug_println!("{}, {}, {}", x, y, z);
 
             let mut v = Vertex::default();
-            v.p.x = x;
-            v.p.y = y;
-            v.p.z = z;
+            v.p = (x, y, z).into();
+
+            if !v.p.is_finite() {
+                return Err(Error::other(format!(
+                    "Invalid vertex position detected at vertex {}",
+                    i
+                )));
+            }
 
             edit_mesh.vList.push(v);
         }
     }
 
+    // Make sure the triangles only refer to things we actually loaded.
+    // Degenerate triangles are allowed, and get cleaned up later
+    if let Err(msg) = edit_mesh.index_check() {
+        return Err(Error::other(msg));
+    }
+
     Ok(edit_mesh)
 }
@@ -0,0 +1,69 @@
+use crate::aabb3::AABB3;
+use crate::vector3::Vector3;
+
+// Implement a sphere, for collision and culling tests where a box would be
+// too loose, or too expensive to rotate.  See 12.3
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub fn new(center: Vector3, radius: f32) -> Sphere {
+        Sphere { center, radius }
+    }
+
+    // Return true if the point is inside the sphere.  Points on the surface
+    // count as inside
+    pub fn contains(&self, p: &Vector3) -> bool {
+        self.center.distance_squared_to(p) <= self.radius * self.radius
+    }
+
+    // Return true if two spheres intersect.  Spheres that only touch do not
+    // count, the same as AABB3::intersects_sphere
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        let r = self.radius + other.radius;
+        self.center.distance_squared_to(&other.center) < r * r
+    }
+
+    // Return true if we intersect a box.  See AABB3::intersects_sphere
+    pub fn intersects_aabb(&self, b: &AABB3) -> bool {
+        b.intersects_sphere(&self.center, self.radius)
+    }
+
+    // Parametric intersection with a ray.  Returns the smallest t >= 0 such
+    // that ray_org + ray_dir * t is on the surface, or None if the ray misses
+    // or the sphere is behind it.  If the ray starts inside the sphere, 0 is
+    // returned, as in AABB3::ray_intersect.  If ray_dir is the full length of
+    // a segment, check t <= 1
+    pub fn ray_intersect(&self, ray_org: &Vector3, ray_dir: &Vector3) -> Option<f32> {
+        // Solve |ray_org + ray_dir * t - center|^2 = radius^2 for t, which
+        // is the quadratic a t^2 + 2 b t + c = 0
+        let e = *ray_org - self.center;
+        let a = ray_dir.dot(ray_dir);
+        let b = e.dot(ray_dir);
+        let c = e.dot(&e) - self.radius * self.radius;
+
+        // Starting inside?
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+
+        // Outside and pointing away, or no direction at all?
+        if b >= 0.0 || a == 0.0 {
+            return None;
+        }
+
+        // Check the discriminant.  Negative means we pass by the sphere
+        let discriminant = b * b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        // Take the nearer of the two roots, where we enter the sphere
+        Some((-b - discriminant.sqrt()) / a)
+    }
+}
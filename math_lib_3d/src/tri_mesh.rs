@@ -36,14 +36,12 @@ impl TriMesh {
     // Constructor - reset internal variables to default (empty) state
 
     pub fn default() -> Self {
-        let mut bounding_box = AABB3::new();
-        bounding_box.empty();
         TriMesh {
             vertexCount: 0,
             vertexList: Vec::new(),
             triCount: 0,
             triList: Vec::new(),
-            bounding_box,
+            bounding_box: AABB3::new(),
         }
     }
 
@@ -173,7 +171,7 @@ impl TriMesh {
             // self.vertexList[i] = rv;
             //d.p = s.p.clone();
 
-            d.p.copy(&s.p);
+            d.p = s.p;
         }
         /*
         for (i = 0 ; i < vertexCount ; ++i) {
@@ -27,9 +27,12 @@ pub fn wrap_pi(angle: f32) -> f32 {
     angle - PI
 }
 
+// Same as acos(x), but if x is out of range, it is "clamped" to the nearest
+// valid value.  The value returned is in range 0...pi, the same as the
+// standard acos() function
 pub fn safe_acos(x: f32) -> f32 {
     // check limit conditions
-    if x <= 1.0 {
+    if x <= -1.0 {
         PI
     } else if x >= 1.0 {
         0.0
@@ -1,14 +1,105 @@
 #![allow(dead_code)]
 
+use std::fmt;
 use std::ops;
 
+// Tolerance for approximate comparisons.  Single precision floats carry
+// about 7 significant digits, so for geometry measured in units around
+// 1..100 an absolute tolerance of 1e-5 absorbs the rounding error from a
+// handful of transforms without hiding real differences.
+pub const DEFAULT_EPSILON: f32 = 0.00001;
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+// 2D vector, mostly used for texture mapping coordinates
+impl Vec2 {
+    pub fn new(x: f32, y: f32) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    pub fn zero() -> Vec2 {
+        Vec2 { x: 0.0, y: 0.0 }
+    }
+
+    pub fn normalize(&mut self) {
+        let mag_sq = self.x * self.x + self.y * self.y;
+        if mag_sq > 0.0 {
+            let one_over_mag = 1.0 / mag_sq.sqrt();
+            self.x *= one_over_mag;
+            self.y *= one_over_mag;
+        }
+    }
+
+    // dot product
+    pub fn dot(&self, other: &Vec2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    // Linear interpolation, a + (b - a) * t.  t is not clamped
+    pub fn lerp(a: &Vec2, b: &Vec2, t: f32) -> Vec2 {
+        Vec2 {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+        }
+    }
+}
+
+impl ops::Add<&Vec2> for &Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: &Vec2) -> Self::Output {
+        Vec2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl ops::Sub<&Vec2> for &Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: &Vec2) -> Self::Output {
+        Vec2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+// Scalar multiple
+impl ops::Mul<f32> for &Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, a: f32) -> Self::Output {
+        Vec2 {
+            x: self.x * a,
+            y: self.y * a,
+        }
+    }
+}
+
+// Scalar divide
+impl ops::Div<f32> for &Vec2 {
+    type Output = Vec2;
+
+    fn div(self, a: f32) -> Self::Output {
+        Vec2 {
+            x: self.x / a,
+            y: self.y / a,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
@@ -50,12 +141,22 @@ impl Vector3 {
         (self.x != other.x) | (self.y != other.y) | (self.z != other.z)
     }
 
-    pub fn neg(&self) {
-        Vector3 {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
-        };
+    // Return true if no component is infinite or NaN
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    // Return true if any component is NaN
+    pub fn has_nan(&self) -> bool {
+        self.x.is_nan() || self.y.is_nan() || self.z.is_nan()
+    }
+
+    // Return true if each component is within epsilon of the other vector.
+    // Use this instead of == for results of floating point computations.
+    pub fn approx_eq(&self, other: &Vector3, epsilon: f32) -> bool {
+        ((self.x - other.x).abs() <= epsilon)
+            && ((self.y - other.y).abs() <= epsilon)
+            && ((self.z - other.z).abs() <= epsilon)
     }
 
     pub fn add(&self, other: &Vector3) -> Vector3 {
@@ -90,6 +191,14 @@ impl Vector3 {
         }
     }
 
+    // Return a unit length copy of the vector.  A zero vector is
+    // returned unchanged, matching normalize()
+    pub fn normalized(&self) -> Vector3 {
+        let mut result = *self;
+        result.normalize();
+        result
+    }
+
     // dot product
     pub fn dot(&self, other: &Vector3) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
@@ -98,6 +207,173 @@ impl Vector3 {
     pub fn magnitude(&self) -> f32 {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
+
+    // Check if the vector has (approximately) unit length.  The squared
+    // magnitude is compared, which avoids a square root and is close
+    // enough for the small tolerances used in practice
+    pub fn is_unit(&self, epsilon: f32) -> bool {
+        (self.dot(self) - 1.0).abs() < epsilon
+    }
+
+    // cross product, self x other.  Same as cross_product(self, other)
+    pub fn cross(&self, other: &Vector3) -> Vector3 {
+        cross_product(self, other)
+    }
+
+    // Distance from this point to another.  Same as distance(self, other)
+    pub fn distance_to(&self, other: &Vector3) -> f32 {
+        distance(self, other)
+    }
+
+    // Squared distance from this point to another.  Cheaper than
+    // distance_to() when only comparing distances
+    pub fn distance_squared_to(&self, other: &Vector3) -> f32 {
+        distance_squared(self, other)
+    }
+
+    // Reflect the vector about a surface normal, self - 2(self.n)n
+    //
+    // normal must be a unit vector.
+    pub fn reflect(&self, normal: &Vector3) -> Vector3 {
+        // Quick sanity check to make sure they passed in a unit vector
+        assert!((normal.dot(normal) - 1.0).abs() < 0.01);
+
+        let k = 2.0 * self.dot(normal);
+        Vector3 {
+            x: self.x - k * normal.x,
+            y: self.y - k * normal.y,
+            z: self.z - k * normal.z,
+        }
+    }
+
+    // Return the component of the vector parallel to onto.
+    // A (near) zero length onto has no direction, so the zero
+    // vector is returned
+    pub fn project_onto(&self, onto: &Vector3) -> Vector3 {
+        let mag_sq = onto.dot(onto);
+        if mag_sq < 0.000001 {
+            return Vector3::zero();
+        }
+        onto * (self.dot(onto) / mag_sq)
+    }
+
+    // Return the component of the vector perpendicular to onto
+    pub fn reject_from(&self, onto: &Vector3) -> Vector3 {
+        *self - self.project_onto(onto)
+    }
+
+    // Rotate the vector about an arbitrary axis through the origin using
+    // Rodrigues' formula:
+    //
+    //     v' = v cos(theta) + (axis x v) sin(theta) + axis (axis.v) (1 - cos(theta))
+    //
+    // axis must be a unit vector.  theta is in radians, and gives the same
+    // result as Matrix4x3::setup_rotate_from_vector.
+    pub fn rotate_about_axis(&self, axis: &Vector3, theta: f32) -> Vector3 {
+        // Quick sanity check to make sure they passed in a unit vector
+        // to specify the axis
+        assert!((axis.dot(axis) - 1.0).abs() < 0.01);
+
+        let (sin, cos) = theta.sin_cos();
+        let k_cross_v = cross_product(axis, self);
+        let k = axis.dot(self) * (1.0 - cos);
+
+        Vector3 {
+            x: self.x * cos + k_cross_v.x * sin + axis.x * k,
+            y: self.y * cos + k_cross_v.y * sin + axis.y * k,
+            z: self.z * cos + k_cross_v.z * sin + axis.z * k,
+        }
+    }
+
+    // Convert to spherical coordinates (radius, heading, pitch).  See
+    // section 7.3.2.  With +y up and +z forward, heading is the rotation
+    // about the y axis (positive turns right, toward +x) and pitch is the
+    // angle below the horizontal plane (positive looks down).  Angles are
+    // in radians, heading in -pi..pi and pitch in -pi/2..pi/2.
+    //
+    // The zero vector returns all zeros, and straight up or down returns
+    // a heading of zero, so the result is always in canonical form.
+    pub fn to_spherical(&self) -> (f32, f32, f32) {
+        let radius = self.magnitude();
+        if radius == 0.0 {
+            return (0.0, 0.0, 0.0);
+        }
+
+        // Clamp in case rounding pushed us slightly out of range
+        let sin_pitch = (-self.y / radius).clamp(-1.0, 1.0);
+        let pitch = sin_pitch.asin();
+
+        // Check for looking straight up or down, where heading is
+        // undefined
+        let heading = if self.x * self.x + self.z * self.z > 0.0 {
+            self.x.atan2(self.z)
+        } else {
+            0.0
+        };
+
+        (radius, heading, pitch)
+    }
+
+    // Build a vector from spherical coordinates.  The inverse of
+    // to_spherical()
+    pub fn from_spherical(radius: f32, heading: f32, pitch: f32) -> Vector3 {
+        let (sh, ch) = heading.sin_cos();
+        let (sp, cp) = pitch.sin_cos();
+        Vector3 {
+            x: radius * cp * sh,
+            y: -radius * sp,
+            z: radius * cp * ch,
+        }
+    }
+
+    // Component-wise minimum of two vectors
+    pub fn min(&self, other: &Vector3) -> Vector3 {
+        Vector3 {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    // Component-wise maximum of two vectors
+    pub fn max(&self, other: &Vector3) -> Vector3 {
+        Vector3 {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    // Clamp each component into the range lo..hi.  The lower bound is
+    // checked first, so if lo > hi on an axis, values below lo clamp to
+    // lo and everything else clamps to hi.
+    pub fn clamp(&self, lo: &Vector3, hi: &Vector3) -> Vector3 {
+        fn clamp_one(v: f32, lo: f32, hi: f32) -> f32 {
+            if v < lo {
+                lo
+            } else if v > hi {
+                hi
+            } else {
+                v
+            }
+        }
+
+        Vector3 {
+            x: clamp_one(self.x, lo.x, hi.x),
+            y: clamp_one(self.y, lo.y, hi.y),
+            z: clamp_one(self.z, lo.z, hi.z),
+        }
+    }
+
+    // Linear interpolation, a + (b - a) * t.  t is not clamped, so values
+    // outside 0..1 extrapolate along the line through a and b
+    pub fn lerp(a: &Vector3, b: &Vector3, t: f32) -> Vector3 {
+        Vector3 {
+            x: a.x + (b.x - a.x) * t,
+            y: a.y + (b.y - a.y) * t,
+            z: a.z + (b.z - a.z) * t,
+        }
+    }
 }
 
 pub fn cross_product(a: &Vector3, b: &Vector3) -> Vector3 {
@@ -108,6 +384,35 @@ pub fn cross_product(a: &Vector3, b: &Vector3) -> Vector3 {
     }
 }
 
+// Scalar triple product a . (b x c).  This is the signed volume of the
+// parallelepiped spanned by the three vectors, and its sign gives the
+// orientation (handedness) of the triple
+pub fn scalar_triple(a: &Vector3, b: &Vector3, c: &Vector3) -> f32 {
+    a.dot(&cross_product(b, c))
+}
+
+// Unsigned volume of the box (parallelepiped) with edges a, b and c
+pub fn box_volume(a: &Vector3, b: &Vector3, c: &Vector3) -> f32 {
+    scalar_triple(a, b, c).abs()
+}
+
+// Average a set of unit normals by summing them and normalizing the
+// result.  Normals that (nearly) cancel out, such as two opposing faces
+// of a thin sheet, have no meaningful average, so if the sum degenerates
+// the zero vector is returned instead of a garbage direction.  An empty
+// slice also returns the zero vector.
+pub fn blend_normals(normals: &[Vector3]) -> Vector3 {
+    let mut sum = Vector3::zero();
+    for n in normals {
+        sum += n;
+    }
+
+    if sum.dot(&sum) < 0.000001 {
+        return Vector3::zero();
+    }
+    sum.normalized()
+}
+
 pub fn distance(a: &Vector3, b: &Vector3) -> f32 {
     let dx = a.x - b.x;
     let dy = a.y - b.y;
@@ -122,18 +427,29 @@ pub fn distance_squared(a: &Vector3, b: &Vector3) -> f32 {
     dx * dx + dy * dy + dz * dz
 }
 
+// Print as (x, y, z).  A precision flag, as in {:.3}, is applied
+// to each component
+impl fmt::Display for Vector3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "({:.*}, {:.*}, {:.*})", p, self.x, p, self.y, p, self.z),
+            None => write!(f, "({}, {}, {})", self.x, self.y, self.z),
+        }
+    }
+}
+
 // impl PartialEq<&Vector3> for &Vector3 {
 //     fn eq(&self, other: &&Vector3) -> bool {
 //         (self.x == other.x) && (self.y == other.y) && (self.z == other.z)
 //     }
 // }
 
-// for operator = (assign) in rust use clone()
+// Vector3 is Copy, so operator = (assign) is plain assignment
 
-impl ops::Add<&Vector3> for &Vector3 {
+impl ops::Add<Vector3> for Vector3 {
     type Output = Vector3;
 
-    fn add(self, rhs: &Vector3) -> Self::Output {
+    fn add(self, rhs: Vector3) -> Self::Output {
         Vector3 {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
@@ -142,10 +458,10 @@ impl ops::Add<&Vector3> for &Vector3 {
     }
 }
 
-impl ops::Sub<&Vector3> for &Vector3 {
+impl ops::Sub<Vector3> for Vector3 {
     type Output = Vector3;
 
-    fn sub(self, rhs: &Vector3) -> Self::Output {
+    fn sub(self, rhs: Vector3) -> Self::Output {
         Vector3 {
             x: self.x - rhs.x,
             y: self.y - rhs.y,
@@ -154,8 +470,138 @@ impl ops::Sub<&Vector3> for &Vector3 {
     }
 }
 
+// Borrowed and mixed operand forms, so &a + &b, a + &b and &a + b all
+// work.  These forward to the owned versions above
+impl ops::Add<&Vector3> for &Vector3 {
+    type Output = Vector3;
+
+    fn add(self, rhs: &Vector3) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl ops::Add<&Vector3> for Vector3 {
+    type Output = Vector3;
+
+    fn add(self, rhs: &Vector3) -> Self::Output {
+        self + *rhs
+    }
+}
+
+impl ops::Add<Vector3> for &Vector3 {
+    type Output = Vector3;
+
+    fn add(self, rhs: Vector3) -> Self::Output {
+        *self + rhs
+    }
+}
+
+impl ops::Sub<&Vector3> for &Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: &Vector3) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl ops::Sub<&Vector3> for Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: &Vector3) -> Self::Output {
+        self - *rhs
+    }
+}
+
+impl ops::Sub<Vector3> for &Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: Vector3) -> Self::Output {
+        *self - rhs
+    }
+}
+
+// Conversions to and from plain arrays and tuples, handy for
+// vertex buffers and file IO
+impl From<[f32; 3]> for Vector3 {
+    fn from(a: [f32; 3]) -> Self {
+        Vector3 {
+            x: a[0],
+            y: a[1],
+            z: a[2],
+        }
+    }
+}
+
+impl From<Vector3> for [f32; 3] {
+    fn from(v: Vector3) -> Self {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl From<(f32, f32, f32)> for Vector3 {
+    fn from(t: (f32, f32, f32)) -> Self {
+        Vector3 {
+            x: t.0,
+            y: t.1,
+            z: t.2,
+        }
+    }
+}
+
+impl From<Vector3> for (f32, f32, f32) {
+    fn from(v: Vector3) -> Self {
+        (v.x, v.y, v.z)
+    }
+}
+
+// Component access by axis index: 0 => x, 1 => y, 2 => z
+impl ops::Index<usize> for Vector3 {
+    type Output = f32;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        match i {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vector3 index out of range: {} (must be 0, 1 or 2)", i),
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Vector3 {
+    fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+        match i {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Vector3 index out of range: {} (must be 0, 1 or 2)", i),
+        }
+    }
+}
+
+// Unary minus
+impl ops::Neg for Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Self::Output {
+        Vector3 {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl ops::Neg for &Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
 // Scalar multiple
-impl ops::Mul<f32> for &Vector3 {
+impl ops::Mul<f32> for Vector3 {
     type Output = Vector3;
 
     fn mul(self, a: f32) -> Self::Output {
@@ -167,10 +613,10 @@ impl ops::Mul<f32> for &Vector3 {
     }
 }
 
-impl ops::Mul<&Vector3> for f32 {
+impl ops::Mul<Vector3> for f32 {
     type Output = Vector3;
 
-    fn mul(self, v: &Vector3) -> Self::Output {
+    fn mul(self, v: Vector3) -> Self::Output {
         Vector3 {
             x: self * v.x,
             y: self * v.y,
@@ -179,8 +625,24 @@ impl ops::Mul<&Vector3> for f32 {
     }
 }
 
+impl ops::Mul<f32> for &Vector3 {
+    type Output = Vector3;
+
+    fn mul(self, a: f32) -> Self::Output {
+        *self * a
+    }
+}
+
+impl ops::Mul<&Vector3> for f32 {
+    type Output = Vector3;
+
+    fn mul(self, v: &Vector3) -> Self::Output {
+        self * *v
+    }
+}
+
 // Scalar divide
-impl ops::Div<f32> for &Vector3 {
+impl ops::Div<f32> for Vector3 {
     type Output = Vector3;
 
     fn div(self, a: f32) -> Self::Output {
@@ -192,6 +654,14 @@ impl ops::Div<f32> for &Vector3 {
     }
 }
 
+impl ops::Div<f32> for &Vector3 {
+    type Output = Vector3;
+
+    fn div(self, a: f32) -> Self::Output {
+        *self / a
+    }
+}
+
 impl ops::AddAssign<&Vector3> for Vector3 {
     fn add_assign(&mut self, other: &Vector3) {
         self.x += other.x;
@@ -208,6 +678,18 @@ impl ops::SubAssign<&Vector3> for Vector3 {
     }
 }
 
+impl ops::AddAssign<Vector3> for Vector3 {
+    fn add_assign(&mut self, other: Vector3) {
+        *self += &other;
+    }
+}
+
+impl ops::SubAssign<Vector3> for Vector3 {
+    fn sub_assign(&mut self, other: Vector3) {
+        *self -= &other;
+    }
+}
+
 // Scalar *=
 impl ops::MulAssign<f32> for Vector3 {
     fn mul_assign(&mut self, a: f32) {
@@ -218,10 +700,12 @@ impl ops::MulAssign<f32> for Vector3 {
 }
 
 // Scalar /=
+// Like the Div operator, no check is made for a zero divisor, so
+// dividing by 0.0 yields infinite or NaN components.
 impl ops::DivAssign<f32> for Vector3 {
     fn div_assign(&mut self, a: f32) {
-        self.x *= a;
-        self.y *= a;
-        self.z *= a;
+        self.x /= a;
+        self.y /= a;
+        self.z /= a;
     }
 }
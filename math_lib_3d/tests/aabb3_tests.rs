@@ -0,0 +1,495 @@
+use math_lib_3d::aabb3::AABB3;
+use math_lib_3d::matrix4x3::{Axis, Matrix4x3};
+use math_lib_3d::vector3::Vector3;
+use std::f32::consts::FRAC_PI_4;
+
+#[test]
+fn test_new_is_empty() {
+    let b = AABB3::new();
+    assert!(b.is_empty());
+    assert!(!b.contains(&Vector3::zero()));
+}
+
+#[test]
+fn test_new_then_add_one_point() {
+    let p = Vector3::new(1.0, -2.0, 3.0);
+
+    let mut b = AABB3::new();
+    b.add_vector3(&p);
+
+    assert!(!b.is_empty());
+    assert_eq!(b.min, p);
+    assert_eq!(b.max, p);
+    assert!(b.contains(&p));
+}
+
+#[test]
+fn test_add_vector3_each_axis() {
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(0.0, 5.0, -3.0));
+    b.add_vector3(&Vector3::new(0.0, -2.0, 7.0));
+
+    assert_eq!(b.min, Vector3::new(0.0, -2.0, -3.0));
+    assert_eq!(b.max, Vector3::new(0.0, 5.0, 7.0));
+}
+
+#[test]
+fn test_add_aabb() {
+    let mut a = AABB3::new();
+    a.add_vector3(&Vector3::new(0.0, 5.0, -3.0));
+    a.add_vector3(&Vector3::new(1.0, 6.0, -1.0));
+
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(-4.0, -2.0, 7.0));
+    b.add_vector3(&Vector3::new(-3.0, 0.0, 8.0));
+
+    a.add_aabb(&b);
+    assert_eq!(a.min, Vector3::new(-4.0, -2.0, -3.0));
+    assert_eq!(a.max, Vector3::new(1.0, 6.0, 8.0));
+
+    // Adding an empty box changes nothing
+    let before = a;
+    a.add_aabb(&AABB3::new());
+    assert_eq!(a, before);
+
+    // Adding to an empty box gives the same box
+    let mut c = AABB3::new();
+    c.add_aabb(&b);
+    assert_eq!(c, b);
+}
+
+#[test]
+fn test_add_vector3_triangle() {
+    let vertices = [
+        Vector3::new(1.0, 0.5, -2.0),
+        Vector3::new(-3.0, 2.0, 0.0),
+        Vector3::new(0.0, -1.5, 4.0),
+    ];
+
+    let mut b = AABB3::new();
+    for v in &vertices {
+        b.add_vector3(v);
+    }
+
+    assert_eq!(b.min, Vector3::new(-3.0, -1.5, -2.0));
+    assert_eq!(b.max, Vector3::new(1.0, 2.0, 4.0));
+    assert_eq!(b.size(), Vector3::new(4.0, 3.5, 6.0));
+    for v in &vertices {
+        assert!(b.contains(v));
+    }
+}
+
+fn unit_cube() -> AABB3 {
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(0.0, 0.0, 0.0));
+    b.add_vector3(&Vector3::new(1.0, 1.0, 1.0));
+    b
+}
+
+#[test]
+fn test_volume_area_diagonal() {
+    let b = unit_cube();
+    assert_eq!(b.volume(), 1.0);
+    assert_eq!(b.surface_area(), 6.0);
+    assert!((b.diagonal_length() - 3.0f32.sqrt()).abs() < 0.000001);
+
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(-1.0, 0.0, 2.0));
+    b.add_vector3(&Vector3::new(1.0, 3.0, 6.0));
+    assert_eq!(b.volume(), 24.0);
+    assert_eq!(b.surface_area(), 2.0 * (6.0 + 12.0 + 8.0));
+    assert!((b.diagonal_length() - 29.0f32.sqrt()).abs() < 0.000001);
+
+    // A single point is not empty, it just has no size
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(b.volume(), 0.0);
+    assert_eq!(b.diagonal_length(), 0.0);
+}
+
+#[test]
+fn test_volume_area_diagonal_empty() {
+    let b = AABB3::new();
+    assert_eq!(b.volume(), 0.0);
+    assert_eq!(b.surface_area(), 0.0);
+    assert_eq!(b.diagonal_length(), 0.0);
+}
+
+#[test]
+fn test_inflate() {
+    let mut b = unit_cube();
+    b.inflate(0.5);
+    assert_eq!(b.min, Vector3::new(-0.5, -0.5, -0.5));
+    assert_eq!(b.max, Vector3::new(1.5, 1.5, 1.5));
+    assert_eq!(b.volume(), 8.0);
+
+    // Shrinking by more than half the size turns it inside out
+    let mut b = unit_cube();
+    b.inflate(-2.0);
+    assert!(b.is_empty());
+    assert_eq!(b.volume(), 0.0);
+}
+
+#[test]
+fn test_expanded() {
+    let b = unit_cube();
+
+    let grown = b.expanded(0.5);
+    assert_eq!(grown.min, Vector3::new(-0.5, -0.5, -0.5));
+    assert_eq!(grown.max, Vector3::new(1.5, 1.5, 1.5));
+
+    let shrunk = b.expanded(-0.25);
+    assert_eq!(shrunk.min, Vector3::new(0.25, 0.25, 0.25));
+    assert_eq!(shrunk.max, Vector3::new(0.75, 0.75, 0.75));
+
+    assert!(b.expanded(-2.0).is_empty());
+
+    // The original is untouched
+    assert_eq!(b.min, Vector3::zero());
+    assert_eq!(b.max, Vector3::new(1.0, 1.0, 1.0));
+}
+
+#[test]
+fn test_from_points() {
+    let b = AABB3::from_points(&[
+        Vector3::new(1.0, 0.5, -2.0),
+        Vector3::new(-3.0, 2.0, 0.0),
+        Vector3::new(0.0, -1.5, 4.0),
+        Vector3::new(0.5, 0.5, 0.5),
+    ]);
+    assert_eq!(b.min, Vector3::new(-3.0, -1.5, -2.0));
+    assert_eq!(b.max, Vector3::new(1.0, 2.0, 4.0));
+
+    assert!(AABB3::from_points(&[]).is_empty());
+}
+
+#[test]
+fn test_merge() {
+    let a = unit_cube();
+    let b = AABB3::from_points(&[Vector3::new(3.0, -2.0, 5.0), Vector3::new(4.0, -1.0, 6.0)]);
+
+    let m = AABB3::merge(&a, &b);
+    assert_eq!(m.min, Vector3::new(0.0, -2.0, 0.0));
+    assert_eq!(m.max, Vector3::new(4.0, 1.0, 6.0));
+
+    // Order doesn't matter
+    let m2 = AABB3::merge(&b, &a);
+    assert_eq!(m2, m);
+
+    // Empty boxes contribute nothing, however they are inverted
+    let m = AABB3::merge(&a, &AABB3::new());
+    assert_eq!(m, a);
+
+    let shrunk = b.expanded(-2.0);
+    assert!(shrunk.is_empty());
+    let m = AABB3::merge(&shrunk, &a);
+    assert_eq!(m, a);
+
+    assert!(AABB3::merge(&AABB3::new(), &AABB3::new()).is_empty());
+}
+
+#[test]
+fn test_overlaps() {
+    let a = unit_cube();
+
+    // Partial overlap
+    let b = AABB3::from_points(&[Vector3::new(0.5, 0.5, 0.5), Vector3::new(2.0, 2.0, 2.0)]);
+    assert!(a.overlaps(&b));
+    assert!(b.overlaps(&a));
+
+    // Touching on a shared face counts
+    let touching = AABB3::from_points(&[Vector3::new(1.0, 0.0, 0.0), Vector3::new(2.0, 1.0, 1.0)]);
+    assert!(a.overlaps(&touching));
+
+    // Fully contained
+    let inner = a.expanded(-0.25);
+    assert!(a.overlaps(&inner));
+    assert!(inner.overlaps(&a));
+
+    // Disjoint
+    let disjoint = AABB3::from_points(&[Vector3::new(1.1, 0.0, 0.0), Vector3::new(2.0, 1.0, 1.0)]);
+    assert!(!a.overlaps(&disjoint));
+    assert!(!disjoint.overlaps(&a));
+
+    assert!(!a.overlaps(&AABB3::new()));
+}
+
+#[test]
+fn test_contains_aabb() {
+    let a = unit_cube();
+
+    let inner = a.expanded(-0.25);
+    assert!(a.contains_aabb(&inner));
+    assert!(!inner.contains_aabb(&a));
+
+    // A box contains itself, since faces may touch
+    assert!(a.contains_aabb(&a));
+
+    // Touching from outside, partial overlap, and disjoint
+    let touching = AABB3::from_points(&[Vector3::new(1.0, 0.0, 0.0), Vector3::new(2.0, 1.0, 1.0)]);
+    assert!(!a.contains_aabb(&touching));
+    let partial = AABB3::from_points(&[Vector3::new(0.5, 0.5, 0.5), Vector3::new(2.0, 2.0, 2.0)]);
+    assert!(!a.contains_aabb(&partial));
+    let disjoint = AABB3::from_points(&[Vector3::new(3.0, 3.0, 3.0), Vector3::new(4.0, 4.0, 4.0)]);
+    assert!(!a.contains_aabb(&disjoint));
+
+    assert!(a.contains_aabb(&AABB3::new()));
+}
+
+#[test]
+fn test_corners() {
+    let b = AABB3::from_points(&[Vector3::new(-1.0, -2.0, -3.0), Vector3::new(4.0, 5.0, 6.0)]);
+
+    let expected = [
+        Vector3::new(-1.0, -2.0, -3.0),
+        Vector3::new(4.0, -2.0, -3.0),
+        Vector3::new(-1.0, 5.0, -3.0),
+        Vector3::new(4.0, 5.0, -3.0),
+        Vector3::new(-1.0, -2.0, 6.0),
+        Vector3::new(4.0, -2.0, 6.0),
+        Vector3::new(-1.0, 5.0, 6.0),
+        Vector3::new(4.0, 5.0, 6.0),
+    ];
+
+    let corners = b.corners();
+    assert_eq!(corners, expected);
+    for (i, c) in corners.iter().enumerate() {
+        assert_eq!(*c, b.corner(i as i32));
+    }
+}
+
+#[test]
+fn test_center_extents() {
+    let b = AABB3::from_points(&[Vector3::new(-1.0, -2.0, -3.0), Vector3::new(4.0, 5.0, 6.0)]);
+
+    assert_eq!(b.center(), Vector3::new(1.5, 1.5, 1.5));
+    assert_eq!(b.extents(), Vector3::new(2.5, 3.5, 4.5));
+    assert_eq!(b.center() + b.extents(), b.max);
+    assert_eq!(b.center() - b.extents(), b.min);
+}
+
+#[test]
+fn test_ray_intersect_hit() {
+    let b = unit_cube();
+
+    // Hit the -x face halfway along the ray
+    let mut normal = Vector3::zero();
+    let t = b.ray_intersect(
+        &Vector3::new(-2.0, 0.5, 0.5),
+        &Vector3::new(4.0, 0.0, 0.0),
+        Some(&mut normal),
+    );
+    assert!((t - 0.5).abs() < 0.000001);
+    assert_eq!(normal, Vector3::new(-1.0, 0.0, 0.0));
+
+    // Coming down onto the top face at an angle
+    let t = b.ray_intersect(
+        &Vector3::new(0.0, 3.0, 0.5),
+        &Vector3::new(1.0, -4.0, 0.0),
+        Some(&mut normal),
+    );
+    assert!((t - 0.5).abs() < 0.000001);
+    assert_eq!(normal, Vector3::new(0.0, 1.0, 0.0));
+
+    // The normal is optional
+    let t = b.ray_intersect(
+        &Vector3::new(0.5, 0.5, 5.0),
+        &Vector3::new(0.0, 0.0, -10.0),
+        None,
+    );
+    assert!((t - 0.4).abs() < 0.000001);
+}
+
+#[test]
+fn test_ray_intersect_miss() {
+    let b = unit_cube();
+
+    // Pointing away
+    let t = b.ray_intersect(
+        &Vector3::new(-2.0, 0.5, 0.5),
+        &Vector3::new(-4.0, 0.0, 0.0),
+        None,
+    );
+    assert_eq!(t, f32::MAX);
+
+    // Passing beside the box
+    let t = b.ray_intersect(
+        &Vector3::new(-2.0, 1.5, 0.5),
+        &Vector3::new(4.0, 0.0, 0.0),
+        None,
+    );
+    assert_eq!(t, f32::MAX);
+
+    // Too short to reach it
+    let t = b.ray_intersect(
+        &Vector3::new(-2.0, 0.5, 0.5),
+        &Vector3::new(1.0, 0.0, 0.0),
+        None,
+    );
+    assert_eq!(t, f32::MAX);
+}
+
+#[test]
+fn test_ray_intersect_inside() {
+    let b = unit_cube();
+
+    let mut normal = Vector3::zero();
+    let t = b.ray_intersect(
+        &Vector3::new(0.5, 0.5, 0.5),
+        &Vector3::new(0.0, 2.0, 0.0),
+        Some(&mut normal),
+    );
+    assert_eq!(t, 0.0);
+    assert_eq!(normal, Vector3::new(0.0, -1.0, 0.0));
+}
+
+#[test]
+fn test_transform_translation() {
+    let b = unit_cube();
+
+    let mut m = Matrix4x3::identity();
+    m.setup_translation(&Vector3::new(1.0, -2.0, 3.0));
+
+    let t = b.transform(&m);
+    assert_eq!(t.min, Vector3::new(1.0, -2.0, 3.0));
+    assert_eq!(t.max, Vector3::new(2.0, -1.0, 4.0));
+}
+
+#[test]
+fn test_transform_rotation() {
+    // Unit box centered on the origin, turned 45 degrees about y.  The
+    // corners swing out to sqrt(2)/2 on x and z
+    let b = AABB3::from_points(&[Vector3::new(-0.5, -0.5, -0.5), Vector3::new(0.5, 0.5, 0.5)]);
+
+    let mut m = Matrix4x3::identity();
+    m.setup_rotate(Axis::Y, FRAC_PI_4);
+    let t = b.transform(&m);
+
+    let r = 0.5f32.sqrt();
+    assert!(t.min.approx_eq(&Vector3::new(-r, -0.5, -r), 0.00001));
+    assert!(t.max.approx_eq(&Vector3::new(r, 0.5, r), 0.00001));
+
+    // Same as the mutating version
+    let mut expected = AABB3::new();
+    expected.set_to_transformed_box(&b, &m);
+    assert_eq!(t, expected);
+
+    // Transforming an empty box gives an empty box
+    assert!(AABB3::new().transform(&m).is_empty());
+}
+
+#[test]
+fn test_bounding_sphere() {
+    let (center, radius) = unit_cube().bounding_sphere();
+    assert_eq!(center, Vector3::new(0.5, 0.5, 0.5));
+    assert!((radius - 3.0f32.sqrt() / 2.0).abs() < 0.000001);
+
+    // Every corner is on the sphere
+    for c in unit_cube().corners() {
+        assert!((c.distance_to(&center) - radius).abs() < 0.000001);
+    }
+
+    let (_, radius) = AABB3::new().bounding_sphere();
+    assert_eq!(radius, 0.0);
+}
+
+#[test]
+fn test_copy_and_eq() {
+    let original = unit_cube();
+
+    let mut copy = original;
+    assert_eq!(copy, original);
+
+    copy.inflate(0.5);
+    assert_ne!(copy, original);
+    assert_eq!(original, unit_cube());
+}
+
+#[test]
+fn test_closest_point_to() {
+    let b = unit_cube();
+
+    // Inside, the point is its own closest point
+    let p = Vector3::new(0.25, 0.5, 0.75);
+    assert_eq!(b.closest_point_to(&p), p);
+
+    // Outside one face, clamped onto that face
+    assert_eq!(
+        b.closest_point_to(&Vector3::new(0.25, 3.0, 0.75)),
+        Vector3::new(0.25, 1.0, 0.75)
+    );
+
+    // Outside an edge and a corner
+    assert_eq!(
+        b.closest_point_to(&Vector3::new(-2.0, 0.5, 5.0)),
+        Vector3::new(0.0, 0.5, 1.0)
+    );
+    assert_eq!(
+        b.closest_point_to(&Vector3::new(2.0, -1.0, 3.0)),
+        Vector3::new(1.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn test_intersects_sphere() {
+    let b = unit_cube();
+
+    // Center inside
+    assert!(b.intersects_sphere(&Vector3::new(0.5, 0.5, 0.5), 0.1));
+
+    // Reaching the +x face from outside, or falling short
+    assert!(b.intersects_sphere(&Vector3::new(1.5, 0.5, 0.5), 0.6));
+    assert!(!b.intersects_sphere(&Vector3::new(1.5, 0.5, 0.5), 0.4));
+
+    // Near a corner, the distance is to the corner, not the faces
+    let center = Vector3::new(1.5, 1.5, 1.5);
+    assert!(!b.intersects_sphere(&center, 0.8));
+    assert!(b.intersects_sphere(&center, 0.9));
+}
+
+#[test]
+fn test_classify_plane() {
+    let b = unit_cube();
+    let n = Vector3::new(0.0, 1.0, 0.0);
+
+    // Plane y = -1, box entirely in front
+    assert_eq!(b.classify_plane(&n, -1.0), 1);
+
+    // Plane y = 0.5 cuts through the box
+    assert_eq!(b.classify_plane(&n, 0.5), 0);
+
+    // Plane y = 2, box entirely behind
+    assert_eq!(b.classify_plane(&n, 2.0), -1);
+
+    // Tilted plane through the middle
+    let n = Vector3::new(0.6, 0.8, 0.0);
+    assert_eq!(b.classify_plane(&n, 0.7), 0);
+    assert_eq!(b.classify_plane(&n, 1.5), -1);
+    assert_eq!(b.classify_plane(&(-n), 0.1), -1);
+}
+
+#[test]
+fn test_intersect_plane() {
+    let b = AABB3::from_points(&[Vector3::new(0.0, 2.0, 0.0), Vector3::new(1.0, 3.0, 1.0)]);
+    let n = Vector3::new(0.0, 1.0, 0.0);
+
+    // Dropping straight down onto y = 0 from a height of 2
+    let t = b.intersect_plane(&n, 0.0, &Vector3::new(0.0, -1.0, 0.0));
+    assert!((t - 2.0).abs() < 0.00001);
+
+    // At an angle, it has farther to go
+    let t = b.intersect_plane(&n, 0.0, &Vector3::new(0.0, -0.6, 0.8));
+    assert!((t - 2.0 / 0.6).abs() < 0.00001);
+
+    // Moving away from the plane never hits
+    let t = b.intersect_plane(&n, 0.0, &Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(t, f32::MAX);
+
+    // Already penetrating
+    let t = b.intersect_plane(&n, 2.5, &Vector3::new(0.0, -1.0, 0.0));
+    assert_eq!(t, 0.0);
+
+    // Already completely behind
+    let t = b.intersect_plane(&n, 5.0, &Vector3::new(0.0, -1.0, 0.0));
+    assert_eq!(t, f32::MAX);
+}
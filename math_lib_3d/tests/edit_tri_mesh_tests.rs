@@ -0,0 +1,580 @@
+use math_lib_3d::edit_tri_mesh::{EditTriMesh, Material, NormalWeighting, Part, Tri, Vertex};
+use math_lib_3d::vector3::Vector3;
+
+fn make_vertex(x: f32, y: f32, z: f32) -> Vertex {
+    let mut v = Vertex::default();
+    v.p = Vector3::new(x, y, z);
+    v
+}
+
+fn make_tri(a: usize, b: usize, c: usize, part: usize, material: usize) -> Tri {
+    let mut t = Tri::default();
+    t.v[0].index = a;
+    t.v[1].index = b;
+    t.v[2].index = c;
+    t.part = part;
+    t.material = material;
+    t
+}
+
+fn make_material(name: &str) -> Material {
+    let mut m = Material::default();
+    m.diffuseTextureName = name.to_string();
+    m
+}
+
+fn make_part(name: &str) -> Part {
+    let mut p = Part::default();
+    p.name = name.to_string();
+    p
+}
+
+// Three materials and three parts, with one triangle for each
+// (part, material) pair along the diagonal plus a few extras
+fn multi_mesh() -> EditTriMesh {
+    let mut mesh = EditTriMesh::default();
+    for i in 0..6 {
+        mesh.addVertex(make_vertex(i as f32, (i % 2) as f32, 0.0));
+    }
+    for name in ["m0", "m1", "m2"] {
+        mesh.addMaterial(make_material(name));
+    }
+    for name in ["p0", "p1", "p2"] {
+        mesh.addPart(make_part(name));
+    }
+
+    mesh.addTri(make_tri(0, 1, 2, 0, 0));
+    mesh.addTri(make_tri(1, 2, 3, 1, 1));
+    mesh.addTri(make_tri(2, 3, 4, 2, 2));
+    mesh.addTri(make_tri(3, 4, 5, 0, 2));
+    mesh.addTri(make_tri(0, 2, 4, 2, 0));
+    mesh
+}
+
+#[test]
+fn test_delete_material() {
+    let mut mesh = multi_mesh();
+    mesh.deleteMaterial(1);
+
+    assert_eq!(mesh.materialCount(), 2);
+    assert_eq!(mesh.mList[0].diffuseTextureName, "m0");
+    assert_eq!(mesh.mList[1].diffuseTextureName, "m2");
+
+    // The triangle using m1 is gone, and m2 is now index 1
+    assert_eq!(mesh.triCount(), 4);
+    let materials: Vec<usize> = mesh.tList.iter().map(|t| t.material).collect();
+    assert_eq!(materials, vec![0, 1, 1, 0]);
+
+    // Parts and vertices are untouched
+    let parts: Vec<usize> = mesh.tList.iter().map(|t| t.part).collect();
+    assert_eq!(parts, vec![0, 2, 0, 2]);
+    assert_eq!(mesh.partCount(), 3);
+    assert_eq!(mesh.vertexCount(), 6);
+}
+
+#[test]
+fn test_delete_part() {
+    let mut mesh = multi_mesh();
+    mesh.deletePart(1);
+
+    assert_eq!(mesh.partCount(), 2);
+    assert_eq!(mesh.pList[0].name, "p0");
+    assert_eq!(mesh.pList[1].name, "p2");
+
+    // The triangle in p1 is gone, and p2 is now index 1
+    assert_eq!(mesh.triCount(), 4);
+    let parts: Vec<usize> = mesh.tList.iter().map(|t| t.part).collect();
+    assert_eq!(parts, vec![0, 1, 0, 1]);
+
+    // Material indices must not be touched
+    let materials: Vec<usize> = mesh.tList.iter().map(|t| t.material).collect();
+    assert_eq!(materials, vec![0, 2, 2, 0]);
+    assert_eq!(mesh.materialCount(), 3);
+}
+
+#[test]
+fn test_delete_last_material_and_part() {
+    // These indices are past the end of the material and part lists, but
+    // not the vertex list
+    let mut mesh = multi_mesh();
+    mesh.deleteMaterial(2);
+    mesh.deletePart(2);
+
+    assert_eq!(mesh.materialCount(), 2);
+    assert_eq!(mesh.partCount(), 2);
+    assert_eq!(mesh.triCount(), 2);
+    for t in mesh.tList.iter() {
+        assert!(t.material < 2);
+        assert!(t.part < 2);
+    }
+}
+
+#[test]
+#[should_panic(expected = "partIndex out of range")]
+#[cfg(debug_assertions)]
+fn test_delete_part_out_of_range() {
+    // Fewer parts than vertices, so this used to slip past the check
+    let mut mesh = multi_mesh();
+    mesh.deletePart(4);
+}
+
+#[test]
+fn test_extract_parts() {
+    // Two parts that share no vertices, with the triangles interleaved so
+    // each part has triangles after one from the other part
+    let mut mesh = EditTriMesh::default();
+    for i in 0..7 {
+        mesh.addVertex(make_vertex(i as f32, 0.0, (i % 3) as f32));
+    }
+    for name in ["m0", "m1", "m2"] {
+        mesh.addMaterial(make_material(name));
+    }
+    mesh.addPart(make_part("p0"));
+    mesh.addPart(make_part("p1"));
+
+    mesh.addTri(make_tri(0, 1, 2, 0, 0));
+    mesh.addTri(make_tri(4, 5, 6, 1, 2));
+    mesh.addTri(make_tri(1, 3, 2, 0, 1));
+    mesh.addTri(make_tri(6, 5, 4, 1, 2));
+
+    let mut meshes = vec![EditTriMesh::default(), EditTriMesh::default()];
+    mesh.extractParts(&mut meshes);
+
+    // Part 0 uses vertices 0-3 and materials m0 and m1
+    let p0 = &meshes[0];
+    assert_eq!(p0.partCount(), 1);
+    assert_eq!(p0.pList[0].name, "p0");
+    assert_eq!(p0.triCount(), 2);
+    assert_eq!(p0.vertexCount(), 4);
+    assert_eq!(p0.materialCount(), 2);
+    assert_eq!(p0.mList[0].diffuseTextureName, "m0");
+    assert_eq!(p0.mList[1].diffuseTextureName, "m1");
+
+    // Part 1 uses vertices 4-6 and only m2
+    let p1 = &meshes[1];
+    assert_eq!(p1.partCount(), 1);
+    assert_eq!(p1.pList[0].name, "p1");
+    assert_eq!(p1.triCount(), 2);
+    assert_eq!(p1.vertexCount(), 3);
+    assert_eq!(p1.materialCount(), 1);
+    assert_eq!(p1.mList[0].diffuseTextureName, "m2");
+
+    // Every extracted triangle refers to the same vertex positions and
+    // material as the source triangle it came from
+    let sources = [
+        [&mesh.tList[0], &mesh.tList[2]],
+        [&mesh.tList[1], &mesh.tList[3]],
+    ];
+    for (dst, srcs) in meshes.iter().zip(sources.iter()) {
+        for (t, src) in dst.tList.iter().zip(srcs.iter()) {
+            assert_eq!(t.part, 0);
+            assert_eq!(
+                dst.mList[t.material].diffuseTextureName,
+                mesh.mList[src.material].diffuseTextureName
+            );
+            for j in 0..3 {
+                assert_eq!(dst.vList[t.v[j].index].p, mesh.vList[src.v[j].index].p);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_validity_check_ok() {
+    let mesh = multi_mesh();
+    assert_eq!(mesh.validity_check(), Ok(()));
+
+    // An unset material is allowed
+    let mut mesh = multi_mesh();
+    mesh.addTri(make_tri(0, 1, 5, 1, usize::MAX));
+    assert_eq!(mesh.validity_check(), Ok(()));
+
+    assert_eq!(EditTriMesh::default().validity_check(), Ok(()));
+}
+
+#[test]
+fn test_validity_check_bad_vertex() {
+    let mut mesh = multi_mesh();
+    mesh.addTri(make_tri(0, 6, 1, 0, 0));
+
+    let msg = mesh.validity_check().unwrap_err();
+    assert!(msg.contains("Tri 5"), "{}", msg);
+    assert!(msg.contains("index 6"), "{}", msg);
+}
+
+#[test]
+fn test_validity_check_dangling_material() {
+    let mut mesh = multi_mesh();
+    mesh.deleteMaterial(2);
+    mesh.tList[0].material = 2;
+
+    let msg = mesh.validity_check().unwrap_err();
+    assert!(msg.contains("Tri 0"), "{}", msg);
+    assert!(msg.contains("material 2"), "{}", msg);
+}
+
+#[test]
+fn test_validity_check_bad_part() {
+    let mut mesh = multi_mesh();
+    mesh.addTri(make_tri(0, 1, 2, 3, 0));
+
+    let msg = mesh.validity_check().unwrap_err();
+    assert!(msg.contains("part 3"), "{}", msg);
+}
+
+#[test]
+fn test_validity_check_degenerate() {
+    let mut mesh = multi_mesh();
+    mesh.addTri(make_tri(0, 1, 1, 0, 0));
+
+    let msg = mesh.validity_check().unwrap_err();
+    assert!(msg.contains("degenerate"), "{}", msg);
+}
+
+// The original linear search version of copyUvsIntoVertices, to check the
+// bucketed version against
+fn copy_uvs_naive(mesh: &mut EditTriMesh) {
+    mesh.markAllVertices(0);
+    for tri in mesh.tList.iter_mut() {
+        for i in 0..3 {
+            let uv = (tri.v[i].u, tri.v[i].v);
+            let old = mesh.vList[tri.v[i].index].clone();
+            if old.mark == 0 || (old.u, old.v) == uv {
+                let v = &mut mesh.vList[tri.v[i].index];
+                v.u = uv.0;
+                v.v = uv.1;
+                v.mark = 1;
+                continue;
+            }
+
+            let found = mesh.vList.iter().position(|v| {
+                v.p == old.p && v.normal == old.normal && (v.mark == 0 || (v.u, v.v) == uv)
+            });
+            let index = match found {
+                Some(index) => index,
+                None => {
+                    mesh.vList.push(old);
+                    mesh.vList.len() - 1
+                }
+            };
+            let v = &mut mesh.vList[index];
+            v.u = uv.0;
+            v.v = uv.1;
+            v.mark = 1;
+            tri.v[i].index = index;
+        }
+    }
+}
+
+// A cube with the 8 corners shared by all faces, but each face mapped with
+// the whole texture, so most corners need several different UVs.  Some
+// corners are also duplicated up front, so there are spare vertices to
+// claim before new ones get created
+fn shared_corner_cube() -> EditTriMesh {
+    let mut mesh = EditTriMesh::default();
+    for i in 0..8 {
+        mesh.addVertex(make_vertex(
+            (i & 1) as f32,
+            ((i >> 1) & 1) as f32,
+            ((i >> 2) & 1) as f32,
+        ));
+    }
+    mesh.addVertex(make_vertex(0.0, 0.0, 0.0));
+    mesh.addVertex(make_vertex(1.0, 1.0, 1.0));
+    mesh.addMaterial(make_material("m0"));
+    mesh.addPart(make_part("p0"));
+
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    let uvs = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+    for f in faces.iter() {
+        for [a, b, c] in [[0, 1, 2], [0, 2, 3]] {
+            let mut t = make_tri(f[a], f[b], f[c], 0, 0);
+            for (j, k) in [a, b, c].iter().enumerate() {
+                t.v[j].u = uvs[*k].0;
+                t.v[j].v = uvs[*k].1;
+            }
+            mesh.addTri(t);
+        }
+    }
+    mesh
+}
+
+#[test]
+fn test_copy_uvs_into_vertices() {
+    let mut mesh = shared_corner_cube();
+    let mut naive = mesh.clone();
+
+    mesh.copyUvsIntoVertices();
+    copy_uvs_naive(&mut naive);
+
+    // Same vertices created, and the triangles use them the same way
+    assert!(mesh.vertexCount() > 10);
+    assert_eq!(mesh.vertexCount(), naive.vertexCount());
+    for (a, b) in mesh.vList.iter().zip(naive.vList.iter()) {
+        assert_eq!(a.p, b.p);
+        assert_eq!((a.u, a.v), (b.u, b.v));
+    }
+    for (a, b) in mesh.tList.iter().zip(naive.tList.iter()) {
+        for j in 0..3 {
+            assert_eq!(a.v[j].index, b.v[j].index);
+        }
+    }
+
+    // Every face vertex now has the UVs its triangle wants, at the same
+    // position as before
+    let original = shared_corner_cube();
+    for (t, o) in mesh.tList.iter().zip(original.tList.iter()) {
+        for j in 0..3 {
+            let v = &mesh.vList[t.v[j].index];
+            assert_eq!((v.u, v.v), (t.v[j].u, t.v[j].v));
+            assert_eq!(v.p, original.vList[o.v[j].index].p);
+        }
+    }
+
+    // Doing it again doesn't need any more vertices
+    let count = mesh.vertexCount();
+    mesh.copyUvsIntoVertices();
+    assert_eq!(mesh.vertexCount(), count);
+}
+
+#[test]
+fn test_flip_winding() {
+    let mut mesh = multi_mesh();
+    mesh.vList[4].p.z = 1.0;
+    mesh.vList[5].p.z = -2.0;
+    mesh.computeVertexNormals();
+    let original = mesh.clone();
+
+    mesh.flip_winding();
+
+    // The stored normals are flipped right away
+    for (t, o) in mesh.tList.iter().zip(original.tList.iter()) {
+        assert_eq!(t.v[0].index, o.v[0].index);
+        assert_eq!(t.v[1].index, o.v[2].index);
+        assert_eq!(t.v[2].index, o.v[1].index);
+        assert_eq!(t.normal, -o.normal);
+    }
+    for (v, o) in mesh.vList.iter().zip(original.vList.iter()) {
+        assert_eq!(v.normal, -o.normal);
+    }
+
+    // And they agree with what we compute from the new winding
+    for i in 0..mesh.triCount() {
+        mesh.computeOneTriNormal_with_index(i);
+        assert!(mesh.tList[i]
+            .normal
+            .approx_eq(&-original.tList[i].normal, 0.000001));
+    }
+
+    // Flipping twice gets us back where we started
+    mesh.flip_winding();
+    for (t, o) in mesh.tList.iter().zip(original.tList.iter()) {
+        for j in 0..3 {
+            assert_eq!(t.v[j].index, o.v[j].index);
+        }
+    }
+}
+
+// One large triangle facing -z and one tiny one facing +x, sharing vertex 0
+fn large_and_tiny() -> EditTriMesh {
+    let mut mesh = EditTriMesh::default();
+    mesh.addVertex(make_vertex(0.0, 0.0, 0.0));
+    mesh.addVertex(make_vertex(0.0, 10.0, 0.0));
+    mesh.addVertex(make_vertex(10.0, 0.0, 0.0));
+    mesh.addVertex(make_vertex(0.0, 0.0, 0.1));
+    mesh.addVertex(make_vertex(0.0, 0.1, 0.0));
+    mesh.addMaterial(make_material("m0"));
+    mesh.addPart(make_part("p0"));
+    mesh.addTri(make_tri(0, 1, 2, 0, 0));
+    mesh.addTri(make_tri(0, 4, 3, 0, 0));
+    mesh
+}
+
+#[test]
+fn test_compute_vertex_normals_weighted_area() {
+    let mut mesh = large_and_tiny();
+    mesh.computeTriNormals();
+    assert!(mesh.tList[0]
+        .normal
+        .approx_eq(&Vector3::new(0.0, 0.0, -1.0), 0.000001));
+    assert!(mesh.tList[1]
+        .normal
+        .approx_eq(&Vector3::new(1.0, 0.0, 0.0), 0.000001));
+
+    // Plain summing splits the difference
+    mesh.computeVertexNormals();
+    let plain = mesh.vList[0].normal;
+    assert!((plain.x - (-plain.z)).abs() < 0.000001);
+
+    // Area weighting leans almost all the way toward the large triangle
+    mesh.compute_vertex_normals_weighted(NormalWeighting::Area);
+    let weighted = mesh.vList[0].normal;
+    assert!((weighted.magnitude() - 1.0).abs() < 0.000001);
+    assert!(weighted.z < -0.999);
+    assert!(weighted.x > 0.0);
+    assert!(-weighted.z > -plain.z);
+
+    // Vertices used by only one triangle just get its normal
+    assert!(mesh.vList[1]
+        .normal
+        .approx_eq(&Vector3::new(0.0, 0.0, -1.0), 0.000001));
+    assert!(mesh.vList[3]
+        .normal
+        .approx_eq(&Vector3::new(1.0, 0.0, 0.0), 0.000001));
+}
+
+#[test]
+fn test_compute_vertex_normals_weighted_angle() {
+    // Both triangles have a 90 degree corner at vertex 0, so angle
+    // weighting gives the same answer as plain summing here
+    let mut mesh = large_and_tiny();
+    mesh.compute_vertex_normals_weighted(NormalWeighting::Angle);
+    let n = mesh.vList[0].normal;
+    let expected = Vector3::new(1.0, 0.0, -1.0).normalized();
+    assert!(n.approx_eq(&expected, 0.000001));
+
+    // Split the large triangle in two.  Plain summing now counts it twice,
+    // but the corner angles still add up to 90 degrees
+    let mut mesh = large_and_tiny();
+    mesh.addVertex(make_vertex(5.0, 5.0, 0.0));
+    mesh.tList[0].v[2].index = 5;
+    mesh.addTri(make_tri(0, 5, 2, 0, 0));
+
+    mesh.compute_vertex_normals_weighted(NormalWeighting::Angle);
+    assert!(mesh.vList[0].normal.approx_eq(&expected, 0.000001));
+
+    mesh.computeVertexNormals();
+    assert!(!mesh.vList[0].normal.approx_eq(&expected, 0.01));
+}
+
+#[test]
+fn test_remove_duplicate_vertices() {
+    // Detaching the faces gives every triangle its own three vertices
+    let mut mesh = shared_corner_cube();
+    mesh.detachAllFaces();
+    assert_eq!(mesh.vertexCount(), 36);
+
+    mesh.remove_duplicate_vertices(0.0);
+
+    // Back down to one vertex per corner, with all triangles intact
+    assert_eq!(mesh.vertexCount(), 8);
+    assert_eq!(mesh.triCount(), 12);
+    assert_eq!(mesh.validity_check(), Ok(()));
+
+    // Every triangle still has the same corners it started with
+    let original = shared_corner_cube();
+    for (t, o) in mesh.tList.iter().zip(original.tList.iter()) {
+        for j in 0..3 {
+            assert_eq!(mesh.vList[t.v[j].index].p, original.vList[o.v[j].index].p);
+        }
+    }
+}
+
+#[test]
+fn test_remove_duplicate_vertices_tolerance() {
+    let mut mesh = EditTriMesh::default();
+    mesh.addVertex(make_vertex(0.0, 0.0, 0.0));
+    mesh.addVertex(make_vertex(1.0, 0.0, 0.0));
+    mesh.addVertex(make_vertex(0.0, 1.0, 0.0));
+    mesh.addVertex(make_vertex(1.0, 0.005, 0.0));
+    mesh.addVertex(make_vertex(1.0, 1.0, 0.0));
+    mesh.addVertex(make_vertex(0.0, 1.0, -0.005));
+    mesh.addVertex(make_vertex(5.0, 5.0, 5.0));
+    mesh.addMaterial(make_material("m0"));
+    mesh.addPart(make_part("p0"));
+    mesh.addTri(make_tri(0, 2, 1, 0, 0));
+    mesh.addTri(make_tri(3, 5, 4, 0, 0));
+
+    // A sliver that collapses once 1 and 3 are merged
+    mesh.addTri(make_tri(1, 3, 4, 0, 0));
+
+    // Too tight a tolerance changes nothing but the unused vertex
+    let mut tight = mesh.clone();
+    tight.remove_duplicate_vertices(0.001);
+    assert_eq!(tight.vertexCount(), 6);
+    assert_eq!(tight.triCount(), 3);
+
+    mesh.remove_duplicate_vertices(0.01);
+    assert_eq!(mesh.vertexCount(), 4);
+    assert_eq!(mesh.triCount(), 2);
+    assert_eq!(mesh.validity_check(), Ok(()));
+
+    // Merged vertices keep the position of the first one in the list
+    let second = &mesh.tList[1];
+    assert_eq!(mesh.vList[second.v[0].index].p, Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(mesh.vList[second.v[1].index].p, Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(mesh.vList[second.v[2].index].p, Vector3::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn test_clone_from() {
+    let src = multi_mesh();
+
+    // Start with a bigger mesh, so dst already has room for everything
+    let mut dst = shared_corner_cube();
+    dst.detachAllFaces();
+    for _ in 0..3 {
+        dst.addMaterial(make_material("extra"));
+        dst.addPart(make_part("extra"));
+    }
+    let v_ptr = dst.vList.as_ptr();
+    let t_ptr = dst.tList.as_ptr();
+    let v_capacity = dst.vList.capacity();
+    let t_capacity = dst.tList.capacity();
+
+    dst.clone_from(&src);
+
+    assert_eq!(dst, src.clone());
+    assert_eq!(dst.vList.as_ptr(), v_ptr);
+    assert_eq!(dst.tList.as_ptr(), t_ptr);
+    assert_eq!(dst.vList.capacity(), v_capacity);
+    assert_eq!(dst.tList.capacity(), t_capacity);
+
+    // Copying into an empty mesh works too
+    let mut empty = EditTriMesh::default();
+    empty.clone_from(&src);
+    assert_eq!(empty, src);
+}
+
+#[test]
+fn test_index_check_allows_degenerate() {
+    let mut mesh = multi_mesh();
+    mesh.addTri(make_tri(0, 1, 1, 0, 0));
+    assert_eq!(mesh.index_check(), Ok(()));
+
+    mesh.addTri(make_tri(0, 1, 7, 0, 0));
+    assert!(mesh.index_check().unwrap_err().contains("index 7"));
+}
+
+#[test]
+fn test_copy_uvs_into_vertices_near_equal() {
+    // Spare vertices a hair away from the corners share a bucket with
+    // them, but must not be claimed, since only exact matches can be
+    // shared.  A spare at -0 instead of 0 is an exact match
+    let nudge = |x: f32| f32::from_bits(x.to_bits() + 1);
+    let mut mesh = shared_corner_cube();
+    mesh.vList[8].p.x = nudge(0.0);
+    mesh.vList[9].p.y = nudge(1.0);
+    mesh.addVertex(make_vertex(-0.0, 1.0, 0.0));
+    let mut naive = mesh.clone();
+
+    mesh.copyUvsIntoVertices();
+    copy_uvs_naive(&mut naive);
+
+    assert_eq!(mesh.vertexCount(), naive.vertexCount());
+    for (a, b) in mesh.tList.iter().zip(naive.tList.iter()) {
+        for j in 0..3 {
+            assert_eq!(a.v[j].index, b.v[j].index);
+        }
+    }
+    assert_eq!(mesh.vList[8].mark, 0);
+    assert_eq!(mesh.vList[9].mark, 0);
+    assert_eq!(mesh.vList[10].mark, 1);
+}
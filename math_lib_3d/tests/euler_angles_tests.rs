@@ -0,0 +1,347 @@
+use math_lib_3d::euler_angles::EulerAngles;
+use math_lib_3d::matrix4x3::Matrix4x3;
+use math_lib_3d::quaternion::Quaternion;
+use math_lib_3d::rotation_matrix::RotationMatrix;
+use math_lib_3d::vector3::Vector3;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+fn assert_euler_near(a: &EulerAngles, b: &EulerAngles, epsilon: f32) {
+    assert!(
+        (a.heading - b.heading).abs() < epsilon
+            && (a.pitch - b.pitch).abs() < epsilon
+            && (a.bank - b.bank).abs() < epsilon,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+fn world_to_object_matrix(orientation: &EulerAngles) -> Matrix4x3 {
+    let mut m = Matrix4x3::identity();
+    m.setup_parent_to_local_rotation_matrix(
+        &Vector3::zero(),
+        &RotationMatrix::from_euler_angles(orientation),
+    );
+    m
+}
+
+// A few orientations away from gimbal lock, including pure heading and
+// pure bank
+fn test_orientations() -> Vec<EulerAngles> {
+    [
+        (0.8, 0.0, 0.0),
+        (-2.9, 0.0, 0.0),
+        (0.0, 0.0, 1.3),
+        (0.0, 0.0, -2.2),
+        (0.0, 0.6, 0.0),
+        (1.2, 0.4, -0.7),
+    ]
+    .iter()
+    .map(|&(heading, pitch, bank)| EulerAngles {
+        heading,
+        pitch,
+        bank,
+    })
+    .collect()
+}
+
+#[test]
+fn test_from_object_to_inertial_quaternion() {
+    for orientation in test_orientations() {
+        let mut q = Quaternion::identity();
+        q.set_to_rotate_object_to_inertial(orientation.clone());
+
+        let e = EulerAngles::from_object_to_inertial_quaternion(&q);
+        assert_euler_near(&e, &orientation, 0.0001);
+    }
+}
+
+#[test]
+fn test_from_world_to_object_matrix() {
+    for orientation in test_orientations() {
+        let m = world_to_object_matrix(&orientation);
+
+        let e = EulerAngles::from_world_to_object_matrix(&m);
+        assert_euler_near(&e, &orientation, 0.0001);
+    }
+}
+
+#[test]
+fn test_matrix_gimbal_lock() {
+    // Looking straight down (positive pitch), turned to the right
+    let orientation = EulerAngles {
+        heading: 0.7,
+        pitch: FRAC_PI_2,
+        bank: 0.0,
+    };
+
+    let e = EulerAngles::from_rotation_matrix(&RotationMatrix::from_euler_angles(&orientation));
+    assert_euler_near(&e, &orientation, 0.0001);
+
+    let e = EulerAngles::from_world_to_object_matrix(&world_to_object_matrix(&orientation));
+    assert_euler_near(&e, &orientation, 0.0001);
+
+    // Error creep can push sin(pitch) just past 1.0, which would make asin
+    // return NaN if we didn't catch it as gimbal lock
+    let mut m = RotationMatrix::from_euler_angles(&EulerAngles {
+        heading: -2.0,
+        pitch: -FRAC_PI_2,
+        bank: 0.0,
+    });
+    m.m23 = 1.00001;
+
+    let e = EulerAngles::from_rotation_matrix(&m);
+    assert!(e.heading.is_finite() && e.pitch.is_finite());
+    assert!((e.heading + 2.0).abs() < 0.0001);
+    assert!((e.pitch + FRAC_PI_2).abs() < 0.0001);
+    assert_eq!(e.bank, 0.0);
+}
+
+#[test]
+fn test_quaternion_gimbal_lock() {
+    let orientation = EulerAngles {
+        heading: -1.1,
+        pitch: -FRAC_PI_2,
+        bank: 0.0,
+    };
+
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_object_to_inertial(orientation.clone());
+    let e = EulerAngles::from_object_to_inertial_quaternion(&q);
+    assert_euler_near(&e, &orientation, 0.0001);
+
+    q.set_to_rotate_inertial_to_object(orientation.clone());
+    let e = EulerAngles::from_inertial_to_object_quaternion(&q);
+    assert_euler_near(&e, &orientation, 0.0001);
+}
+
+#[test]
+fn test_to_object_to_inertial_quaternion() {
+    for orientation in test_orientations() {
+        let mut expected = Quaternion::identity();
+        expected.set_to_rotate_object_to_inertial(orientation.clone());
+
+        let q = orientation.to_object_to_inertial_quaternion();
+        assert!(q.approx_eq(&expected, 0.0));
+    }
+}
+
+#[test]
+fn test_to_rotation_matrix() {
+    for orientation in test_orientations() {
+        assert_eq!(
+            orientation.to_rotation_matrix(),
+            RotationMatrix::from_euler_angles(&orientation)
+        );
+    }
+}
+
+#[test]
+fn test_quaternion_round_trip() {
+    // Start from non-canonical angles, so the comparison has to go
+    // through canonize()
+    let orientations = [(4.0, 0.3, -0.5), (-0.2, 0.5, 3.5), (0.3, -0.4, -3.3)];
+
+    for (heading, pitch, bank) in orientations {
+        let mut orientation = EulerAngles {
+            heading,
+            pitch,
+            bank,
+        };
+
+        let q = orientation.to_object_to_inertial_quaternion();
+        let mut e = EulerAngles::from_object_to_inertial_quaternion(&q);
+
+        orientation.canonize();
+        e.canonize();
+        assert_euler_near(&e, &orientation, 0.0001);
+    }
+}
+
+#[test]
+fn test_from_degrees() {
+    let e = EulerAngles::from_degrees(90.0, 0.0, 0.0);
+    assert!((e.heading - FRAC_PI_2).abs() < 0.000001);
+    assert_eq!(e.pitch, 0.0);
+    assert_eq!(e.bank, 0.0);
+
+    let e = EulerAngles::from_degrees(-180.0, 45.0, 30.0);
+    assert_euler_near(
+        &e,
+        &EulerAngles {
+            heading: -PI,
+            pitch: FRAC_PI_4,
+            bank: PI / 6.0,
+        },
+        0.000001,
+    );
+}
+
+#[test]
+fn test_to_degrees() {
+    let (h, p, b) = EulerAngles::from_degrees(30.0, -60.0, 135.0).to_degrees();
+    assert!((h - 30.0).abs() < 0.0001);
+    assert!((p + 60.0).abs() < 0.0001);
+    assert!((b - 135.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_add_sub() {
+    let a = EulerAngles {
+        heading: 1.0,
+        pitch: 0.25,
+        bank: -0.5,
+    };
+    let b = EulerAngles {
+        heading: 0.5,
+        pitch: -0.75,
+        bank: 2.0,
+    };
+
+    let sum = &a + &b;
+    assert_euler_near(
+        &sum,
+        &EulerAngles {
+            heading: 1.5,
+            pitch: -0.5,
+            bank: 1.5,
+        },
+        0.000001,
+    );
+
+    let diff = a.clone() - b.clone();
+    assert_euler_near(
+        &diff,
+        &EulerAngles {
+            heading: 0.5,
+            pitch: 1.0,
+            bank: -2.5,
+        },
+        0.000001,
+    );
+
+    assert_euler_near(&(sum - b), &a, 0.000001);
+}
+
+#[test]
+fn test_wrapped_add() {
+    let e = EulerAngles::from_degrees(350.0, 0.0, 0.0);
+    let delta = EulerAngles::from_degrees(20.0, 0.0, 0.0);
+
+    // Plain addition doesn't wrap
+    let (h, _, _) = (&e + &delta).to_degrees();
+    assert!((h - 370.0).abs() < 0.001);
+
+    let (h, p, b) = e.wrapped_add(&delta).to_degrees();
+    assert!((h - 10.0).abs() < 0.001);
+    assert_eq!(p, 0.0);
+    assert_eq!(b, 0.0);
+}
+
+#[test]
+fn test_default_is_identity() {
+    let e = EulerAngles::default();
+    assert_eq!((e.heading, e.pitch, e.bank), (0.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        format!("{}", EulerAngles::identity()),
+        "h=0.0° p=0.0° b=0.0°"
+    );
+    assert_eq!(
+        format!("{}", EulerAngles::from_degrees(30.0, -45.0, 90.0)),
+        "h=30.0° p=-45.0° b=90.0°"
+    );
+    assert_eq!(
+        format!("{:.2}", EulerAngles::from_degrees(12.5, 0.0, 0.0)),
+        "h=12.50° p=0.00° b=0.00°"
+    );
+}
+
+#[test]
+fn test_is_canonical() {
+    assert!(EulerAngles::identity().is_canonical());
+    assert!(EulerAngles::from_degrees(179.0, -89.0, -179.0).is_canonical());
+    assert!(EulerAngles::from_degrees(45.0, 90.0, 0.0).is_canonical());
+
+    assert!(!EulerAngles::from_degrees(181.0, 0.0, 0.0).is_canonical());
+    assert!(!EulerAngles::from_degrees(0.0, 91.0, 0.0).is_canonical());
+    assert!(!EulerAngles::from_degrees(0.0, 0.0, -181.0).is_canonical());
+
+    // Gimbal lock, with some rotation about the vertical axis assigned
+    // to bank instead of heading
+    assert!(!EulerAngles::from_degrees(45.0, -90.0, 10.0).is_canonical());
+}
+
+#[test]
+fn test_is_canonical_at_pi() {
+    // pi is in the canonical range, but -pi isn't, since it's the same angle
+    let at_pi = EulerAngles {
+        heading: PI,
+        pitch: 0.0,
+        bank: PI,
+    };
+    assert!(at_pi.is_canonical());
+
+    let mut e = EulerAngles {
+        heading: -PI,
+        pitch: 0.0,
+        bank: 0.5,
+    };
+    assert!(!e.is_canonical());
+    e.canonize();
+    assert!(e.is_canonical());
+    assert_eq!(e.heading, PI);
+
+    let mut e = EulerAngles {
+        heading: 0.5,
+        pitch: 0.0,
+        bank: -PI,
+    };
+    assert!(!e.is_canonical());
+    e.canonize();
+    assert!(e.is_canonical());
+    assert_eq!(e.bank, PI);
+
+    // Wrapping around from pi lands on pi, not -pi
+    let mut e = EulerAngles {
+        heading: 3.0 * PI,
+        pitch: 0.0,
+        bank: 0.0,
+    };
+    e.canonize();
+    assert!(e.is_canonical());
+    assert!((e.heading - PI).abs() < 0.00001);
+}
+
+#[test]
+fn test_canonize_random() {
+    // Simple LCG, so the "random" angles are the same every run
+    let mut seed: u32 = 12345;
+    let mut random_angle = || {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        ((seed >> 8) as f32 / (1 << 24) as f32 - 0.5) * 4.0 * PI
+    };
+
+    for i in 0..500 {
+        let mut orientation = EulerAngles {
+            heading: random_angle(),
+            pitch: random_angle(),
+            bank: random_angle(),
+        };
+
+        // Every so often, force gimbal lock
+        if i % 10 == 0 {
+            orientation.pitch = if i % 20 == 0 { FRAC_PI_2 } else { -FRAC_PI_2 };
+        }
+
+        let before = orientation.to_object_to_inertial_quaternion();
+        orientation.canonize();
+        let after = orientation.to_object_to_inertial_quaternion();
+
+        assert!(orientation.is_canonical(), "{:?}", orientation);
+        assert!(after.approx_eq(&before, 0.001), "{:?}", orientation);
+    }
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "glam")]
+
+use glam::{Mat4, Quat, Vec3};
+use math_lib_3d::matrix4x3::Matrix4x3;
+use math_lib_3d::quaternion::Quaternion;
+use math_lib_3d::vector3::*;
+
+#[test]
+fn test_vector3_round_trip() {
+    let v = Vector3::new(1.0, -2.0, 3.5);
+    let g: Vec3 = v.into();
+    assert_eq!(g, Vec3::new(1.0, -2.0, 3.5));
+    assert_eq!(Vector3::from(g), v);
+}
+
+#[test]
+fn test_matrix4x3_transforms_match() {
+    let mut m = Matrix4x3::identity();
+    m.setup_rotate_from_vector(&Vector3::new(0.0, 0.6, 0.8), 0.7);
+    m.set_translation(&Vector3::new(1.0, 2.0, 3.0));
+
+    let g = Mat4::from(&m);
+
+    let p = Vector3::new(4.0, -5.0, 6.0);
+    let expected = p * &m;
+    let actual = Vector3::from(g.transform_point3(p.into()));
+    assert!(actual.approx_eq(&expected, 0.0001));
+
+    // And back again
+    let back = Matrix4x3::from(g);
+    let actual = Vector3::new(4.0, -5.0, 6.0) * &back;
+    assert!(actual.approx_eq(&expected, 0.0001));
+}
+
+#[test]
+fn test_quaternion_rotations_match() {
+    let axis = Vector3::new(0.0, 0.6, 0.8);
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_about_axis(axis, 1.1);
+
+    let p = Vector3::new(1.0, 2.0, 3.0);
+    let expected = p.rotate_about_axis(&axis, 1.1);
+
+    let g: Quat = q.into();
+    let actual = Vector3::from(g * Vec3::from(p));
+    assert!(actual.approx_eq(&expected, 0.0001));
+
+    let back = Quaternion::from(g);
+    assert_eq!(format!("{:?}", back), format!("{:?}", q));
+}
+
+#[test]
+fn test_gl_array_matches_glam() {
+    let mut m = Matrix4x3::from_axis_angle(&Vector3::new(0.0, 0.6, 0.8), 0.7);
+    m.set_translation(&Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(m.to_gl_array(), Mat4::from(&m).to_cols_array());
+}
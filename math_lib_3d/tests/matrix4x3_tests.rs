@@ -0,0 +1,619 @@
+use math_lib_3d::euler_angles::EulerAngles;
+use math_lib_3d::matrix4x3::*;
+use math_lib_3d::vector3::*;
+
+#[test]
+fn test_identity_has_no_translation() {
+    let m = Matrix4x3::identity();
+    assert_eq!(Vector3::zero() * &m, Vector3::zero());
+
+    let p = Vector3::new(1.0, -2.0, 3.0);
+    assert_eq!(p * &m, p);
+
+    let mut m = Matrix4x3::identity();
+    m.set_translation(&Vector3::new(4.0, 5.0, 6.0));
+    m.set_identity();
+    assert_eq!(Vector3::zero() * &m, Vector3::zero());
+}
+
+fn test_matrix_a() -> Matrix4x3 {
+    let mut m = Matrix4x3::identity();
+    m.setup_rotate_from_vector(&Vector3::new(0.0, 0.6, 0.8), 0.7);
+    m.set_translation(&Vector3::new(1.0, 2.0, 3.0));
+    m
+}
+
+fn test_matrix_b() -> Matrix4x3 {
+    let mut m = Matrix4x3::identity();
+    m.setup_shear(Axis::X, 0.5, -0.25);
+    m.set_translation(&Vector3::new(-3.0, 0.5, 2.0));
+    m
+}
+
+#[test]
+fn test_mul_assign_matches_mul() {
+    let expected = test_matrix_a() * test_matrix_b();
+
+    let mut a = test_matrix_a();
+    a *= test_matrix_b();
+
+    assert_eq!(a, expected);
+}
+
+#[test]
+fn test_vector_mul_assign_matches_mul() {
+    let m = test_matrix_a();
+    let p = Vector3::new(4.0, -5.0, 6.0);
+
+    let mut q = p;
+    q *= &m;
+    assert_eq!(q, p * &m);
+}
+
+#[test]
+fn test_transpose_3x3() {
+    let m = test_matrix_b();
+    let t = m.transpose_3x3();
+    assert_eq!(t.m12, m.m21);
+    assert_eq!(t.m13, m.m31);
+    assert_eq!(t.m23, m.m32);
+    assert_eq!(t.m31, m.m13);
+    assert_eq!((t.tx, t.ty, t.tz), (m.tx, m.ty, m.tz));
+}
+
+#[test]
+fn test_transpose_rigid_matches_inverse() {
+    let m = test_matrix_a();
+    assert!(m.transpose_rigid().approx_eq(&inverse(&m).unwrap(), 0.0001));
+
+    // And it really does undo the transform
+    let p = Vector3::new(4.0, -5.0, 6.0);
+    let back = p * &m * &m.transpose_rigid();
+    assert!(back.approx_eq(&p, 0.0001));
+}
+
+#[test]
+fn test_orthonormalize() {
+    let mut m = Matrix4x3::identity();
+    m.m11 = 1.02;
+    m.m12 = 0.03;
+    m.m21 = -0.01;
+    m.m23 = 0.05;
+    m.m32 = 0.04;
+    m.m33 = 0.97;
+    m.set_translation(&Vector3::new(1.0, 2.0, 3.0));
+
+    m.orthonormalize();
+
+    let r1 = Vector3::new(m.m11, m.m12, m.m13);
+    let r2 = Vector3::new(m.m21, m.m22, m.m23);
+    let r3 = Vector3::new(m.m31, m.m32, m.m33);
+    assert!(r1.is_unit(0.0001));
+    assert!(r2.is_unit(0.0001));
+    assert!(r3.is_unit(0.0001));
+    assert!(r1.dot(&r2).abs() < 0.0001);
+    assert!(r1.dot(&r3).abs() < 0.0001);
+    assert!(r2.dot(&r3).abs() < 0.0001);
+
+    // Translation is left alone
+    assert_eq!(get_translation(&m), Vector3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_inverse() {
+    let m = test_matrix_b();
+    let inv = inverse(&m).unwrap();
+    let p = Vector3::new(4.0, -5.0, 6.0);
+    assert!((p * &m * &inv).approx_eq(&p, 0.0001));
+}
+
+#[test]
+fn test_inverse_of_singular_matrix() {
+    let mut m = Matrix4x3::identity();
+    m.setup_scale(&Vector3::new(2.0, 0.0, 1.0));
+    assert!(inverse(&m).is_none());
+
+    let mut m = Matrix4x3::identity();
+    m.setup_projection(&Vector3::new(0.0, 1.0, 0.0));
+    assert!(inverse(&m).is_none());
+}
+
+#[test]
+fn test_transform_point_and_direction() {
+    let mut m = Matrix4x3::identity();
+    m.setup_translation(&Vector3::new(10.0, 20.0, 30.0));
+
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert_eq!(m.transform_point(&v), Vector3::new(11.0, 22.0, 33.0));
+    assert_eq!(m.transform_direction(&v), v);
+
+    // The linear portion applies to both
+    let m = test_matrix_a();
+    assert_eq!(m.transform_point(&v), v * &m);
+    let expected = v * &m - get_translation(&m);
+    assert!(m.transform_direction(&v).approx_eq(&expected, 0.0001));
+}
+
+#[test]
+fn test_transform_points() {
+    let m = test_matrix_a();
+    let src = [
+        Vector3::new(1.0, 2.0, 3.0),
+        Vector3::zero(),
+        Vector3::new(-4.0, 0.5, 8.0),
+    ];
+
+    let mut points = src;
+    m.transform_points(&mut points);
+    for i in 0..src.len() {
+        assert_eq!(points[i], src[i] * &m);
+    }
+
+    let mut dst = [Vector3::zero(); 3];
+    m.transform_points_into(&src, &mut dst);
+    assert_eq!(dst, points);
+}
+
+#[test]
+#[should_panic]
+fn test_transform_points_into_length_mismatch() {
+    let m = Matrix4x3::identity();
+    let src = [Vector3::zero(); 3];
+    let mut dst = [Vector3::zero(); 2];
+    m.transform_points_into(&src, &mut dst);
+}
+
+#[test]
+fn test_look_at() {
+    let eye = Vector3::new(1.0, 2.0, 3.0);
+    let target = Vector3::new(4.0, 2.0, 7.0);
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let m = Matrix4x3::look_at(&eye, &target, &up);
+
+    // The eye is at the camera origin, and the target is straight ahead
+    assert!((eye * &m).approx_eq(&Vector3::zero(), 0.0001));
+    assert!((target * &m).approx_eq(&Vector3::new(0.0, 0.0, 5.0), 0.0001));
+
+    // A point above the eye is above in camera space
+    let above = Vector3::new(1.0, 3.0, 3.0) * &m;
+    assert!(above.approx_eq(&Vector3::new(0.0, 1.0, 0.0), 0.0001));
+
+    // Looking down the world z axis is the identity rotation
+    let m = Matrix4x3::look_at(&Vector3::zero(), &Vector3::new(0.0, 0.0, 1.0), &up);
+    assert!(m.approx_eq(&Matrix4x3::identity(), 0.0001));
+}
+
+#[test]
+fn test_look_at_along_up() {
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let m = Matrix4x3::look_at(&Vector3::zero(), &Vector3::new(0.0, -10.0, 0.0), &up);
+
+    let p = Vector3::new(0.0, -2.0, 0.0) * &m;
+    assert!(!p.has_nan());
+    assert!(p.approx_eq(&Vector3::new(0.0, 0.0, 2.0), 0.0001));
+
+    // Still a pure rotation, with no mirroring
+    assert!((determinant(&m) - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_display_grid() {
+    let m = Matrix4x3::identity();
+    let expected = "\
+|    1.0000    0.0000    0.0000 |
+|    0.0000    1.0000    0.0000 |
+|    0.0000    0.0000    1.0000 |
+|    0.0000    0.0000    0.0000 |";
+    assert_eq!(format!("{}", m), expected);
+
+    let mut m = Matrix4x3::identity();
+    m.setup_translation(&Vector3::new(-12.5, 3.0, 100.25));
+    let expected = "\
+|    1.00    0.00    0.00 |
+|    0.00    1.00    0.00 |
+|    0.00    0.00    1.00 |
+|  -12.50    3.00  100.25 |";
+    assert_eq!(format!("{:.2}", m), expected);
+}
+
+#[test]
+fn test_axis_accessors() {
+    let m = Matrix4x3::identity();
+    assert_eq!(m.right(), Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(m.up(), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(m.forward(), Vector3::new(0.0, 0.0, 1.0));
+
+    // Turning 90 degrees right about y: forward becomes +x and
+    // right becomes -z
+    let mut m = Matrix4x3::identity();
+    m.setup_rotate(Axis::Y, std::f32::consts::FRAC_PI_2);
+    assert!(m.right().approx_eq(&Vector3::new(0.0, 0.0, -1.0), 0.0001));
+    assert!(m.up().approx_eq(&Vector3::new(0.0, 1.0, 0.0), 0.0001));
+    assert!(m.forward().approx_eq(&Vector3::new(1.0, 0.0, 0.0), 0.0001));
+
+    // The rows agree with transforming the local axes as directions
+    let m = test_matrix_a();
+    assert_eq!(
+        m.forward(),
+        m.transform_direction(&Vector3::new(0.0, 0.0, 1.0))
+    );
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_mul_by_reference() {
+    let a = test_matrix_a();
+    let b = test_matrix_b();
+
+    let ab = &a * &b;
+
+    // Both operands are still usable, and the result matches the
+    // by-value form
+    let p = Vector3::new(1.0, -2.0, 3.0);
+    assert!((p * &ab).approx_eq(&(p * &a * &b), 0.0001));
+    assert_eq!(ab, a * b);
+}
+
+#[test]
+fn test_approx_eq() {
+    let a = test_matrix_a();
+    let mut b = a;
+    assert_eq!(a, b);
+    assert!(a.approx_eq(&b, 0.0));
+
+    b.m23 += 0.00001;
+    assert_ne!(a, b);
+    assert!(a.approx_eq(&b, 0.0001));
+    assert!(!a.approx_eq(&b, 0.000001));
+
+    b = a;
+    b.tz += 1.0;
+    assert!(!a.approx_eq(&b, 0.0001));
+}
+
+#[test]
+fn test_inverse_round_trip() {
+    let m = test_matrix_a();
+    let inv = inverse(&m).unwrap();
+    assert!((inv * m).approx_eq(&Matrix4x3::identity(), 0.0001));
+    assert!((m * inv).approx_eq(&Matrix4x3::identity(), 0.0001));
+}
+
+#[test]
+fn test_from_axis_angle() {
+    let z_axis = Vector3::new(0.0, 0.0, 1.0);
+    let m = Matrix4x3::from_axis_angle(&z_axis, std::f32::consts::FRAC_PI_2);
+
+    let p = Vector3::new(1.0, 0.0, 0.0) * &m;
+    assert!(p.approx_eq(&Vector3::new(0.0, 1.0, 0.0), 0.0001));
+    assert_eq!(get_translation(&m), Vector3::zero());
+
+    let mut expected = Matrix4x3::identity();
+    expected.setup_rotate_from_vector(&z_axis, std::f32::consts::FRAC_PI_2);
+    assert_eq!(m, expected);
+}
+
+#[test]
+fn test_is_orthonormal_and_negative_scale() {
+    let m = Matrix4x3::identity();
+    assert!(m.is_orthonormal(0.0001));
+    assert!(!m.has_negative_scale());
+
+    let m = test_matrix_a();
+    assert!(m.is_orthonormal(0.0001));
+    assert!(!m.has_negative_scale());
+
+    // A reflection is orthonormal, but flips handedness
+    let mut m = Matrix4x3::identity();
+    m.setup_reflection_from_axis(Axis::X, 2.0);
+    assert!(m.is_orthonormal(0.0001));
+    assert!(m.has_negative_scale());
+
+    // Scale is not orthonormal
+    let mut m = Matrix4x3::identity();
+    m.setup_scale(&Vector3::new(2.0, 1.0, 1.0));
+    assert!(!m.is_orthonormal(0.0001));
+    assert!(!m.has_negative_scale());
+
+    m.setup_scale(&Vector3::new(1.0, -1.0, 1.0));
+    assert!(m.has_negative_scale());
+
+    // Neither is skew
+    let m = test_matrix_b();
+    assert!(!m.is_orthonormal(0.0001));
+}
+
+#[test]
+fn test_setup_look_along() {
+    let mut m = test_matrix_b();
+    let forward = Vector3::new(1.0, 0.0, 1.0);
+    m.setup_look_along(&forward, &Vector3::new(0.0, 1.0, 0.0));
+
+    assert!(m.forward().approx_eq(&forward.normalized(), 0.0001));
+    assert!(m.up().approx_eq(&Vector3::new(0.0, 1.0, 0.0), 0.0001));
+    assert!(m.is_orthonormal(0.0001));
+    assert!(!m.has_negative_scale());
+    assert_eq!(get_translation(&m), Vector3::zero());
+
+    // Straight up along the up hint still gives a valid rotation
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    m.setup_look_along(&Vector3::new(0.0, 3.0, 0.0), &up);
+    assert!(m.forward().approx_eq(&up, 0.0001));
+    assert!(m.is_orthonormal(0.0001));
+    assert!(!m.has_negative_scale());
+}
+
+#[test]
+fn test_from_basis_round_trip() {
+    let m = test_matrix_a();
+    let rebuilt = Matrix4x3::from_basis(&m.right(), &m.up(), &m.forward(), &get_translation(&m));
+    assert_eq!(rebuilt, m);
+
+    let m = Matrix4x3::from_basis(
+        &Vector3::new(0.0, 0.0, -1.0),
+        &Vector3::new(0.0, 1.0, 0.0),
+        &Vector3::new(1.0, 0.0, 0.0),
+        &Vector3::new(5.0, 0.0, 0.0),
+    );
+    let p = Vector3::new(0.0, 0.0, 2.0) * &m;
+    assert!(p.approx_eq(&Vector3::new(7.0, 0.0, 0.0), 0.0001));
+}
+
+#[test]
+fn test_inverse_rigid_matches_inverse() {
+    let mut m = Matrix4x3::from_axis_angle(&Vector3::new(0.6, 0.0, 0.8), -2.1);
+    m.set_translation(&Vector3::new(-7.0, 3.5, 12.0));
+
+    let fast = m.inverse_rigid();
+    assert!(fast.approx_eq(&inverse(&m).unwrap(), 0.0001));
+    assert!((m * fast).approx_eq(&Matrix4x3::identity(), 0.0001));
+}
+
+#[test]
+fn test_signed_volume_scale() {
+    let m = Matrix4x3::identity();
+    assert_eq!(m.signed_volume_scale(), 1.0);
+    assert!(!m.is_singular(0.000001));
+
+    let mut m = Matrix4x3::identity();
+    m.setup_scale(&Vector3::new(2.0, 2.0, 2.0));
+    assert_eq!(m.signed_volume_scale(), 8.0);
+
+    // Translation doesn't change volumes
+    m.set_translation(&Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(m.signed_volume_scale(), 8.0);
+
+    m.setup_reflection_from_axis(Axis::Z, 0.0);
+    assert_eq!(m.signed_volume_scale(), -1.0);
+
+    m.setup_projection(&Vector3::new(1.0, 0.0, 0.0));
+    assert!(m.is_singular(0.000001));
+}
+
+#[test]
+fn test_to_dx_and_gl_arrays() {
+    let m = Matrix4x3 {
+        m11: 1.0,
+        m12: 2.0,
+        m13: 3.0,
+        m21: 4.0,
+        m22: 5.0,
+        m23: 6.0,
+        m31: 7.0,
+        m32: 8.0,
+        m33: 9.0,
+        tx: 10.0,
+        ty: 11.0,
+        tz: 12.0,
+    };
+
+    let dx = m.to_dx_array();
+    assert_eq!(
+        dx,
+        [1.0, 2.0, 3.0, 0.0, 4.0, 5.0, 6.0, 0.0, 7.0, 8.0, 9.0, 0.0, 10.0, 11.0, 12.0, 1.0]
+    );
+
+    // In the GL array, each column is 4 floats.  The translation is the
+    // last column and the bottom row is [0 0 0 1]
+    let gl = m.to_gl_array();
+    assert_eq!(&gl[12..16], &[10.0, 11.0, 12.0, 1.0]);
+    assert_eq!([gl[3], gl[7], gl[11], gl[15]], [0.0, 0.0, 0.0, 1.0]);
+    assert_eq!(gl, dx);
+}
+
+#[test]
+fn test_setup_rotate_each_axis() {
+    let theta = std::f32::consts::FRAC_PI_2;
+    let mut m = Matrix4x3::identity();
+
+    // Positive rotation is clockwise when looking toward the origin
+    // from the positive end of the axis (left-hand rule)
+    m.setup_rotate(Axis::X, theta);
+    let p = Vector3::new(0.0, 1.0, 0.0) * &m;
+    assert!(p.approx_eq(&Vector3::new(0.0, 0.0, 1.0), 0.0001));
+
+    m.setup_rotate(Axis::Y, theta);
+    let p = Vector3::new(0.0, 0.0, 1.0) * &m;
+    assert!(p.approx_eq(&Vector3::new(1.0, 0.0, 0.0), 0.0001));
+
+    m.setup_rotate(Axis::Z, theta);
+    let p = Vector3::new(1.0, 0.0, 0.0) * &m;
+    assert!(p.approx_eq(&Vector3::new(0.0, 1.0, 0.0), 0.0001));
+
+    // The integer version agrees
+    let mut n = Matrix4x3::identity();
+    assert!(n.setup_rotate_axis(3, theta));
+    assert_eq!(n, m);
+}
+
+#[test]
+fn test_setup_axis_index_wrappers() {
+    let mut a = Matrix4x3::identity();
+    let mut b = Matrix4x3::identity();
+    a.setup_shear(Axis::Y, 0.5, -0.25);
+    assert!(b.setup_shear_axis(2, 0.5, -0.25));
+    assert_eq!(a, b);
+
+    a.setup_reflection_from_axis(Axis::Z, 3.0);
+    assert!(b.setup_reflection_axis(3, 3.0));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_setup_axis_index_wrappers_bad_index() {
+    // A bad index is reported, and the matrix is left alone
+    let original = test_matrix_a();
+    let mut m = original;
+    assert!(!m.setup_rotate_axis(4, 1.0));
+    assert!(!m.setup_shear_axis(0, 0.5, 0.5));
+    assert!(!m.setup_reflection_axis(-1, 2.0));
+    assert_eq!(m, original);
+}
+
+#[test]
+fn test_axis_from_index() {
+    assert_eq!(Axis::from_index(1), Some(Axis::X));
+    assert_eq!(Axis::from_index(2), Some(Axis::Y));
+    assert_eq!(Axis::from_index(3), Some(Axis::Z));
+    assert_eq!(Axis::from_index(0), None);
+
+    assert_eq!(Axis::try_from(2), Ok(Axis::Y));
+    assert_eq!(Axis::try_from(4), Err(4));
+}
+
+#[test]
+fn test_from_trs_round_trip() {
+    let t = Vector3::new(1.0, -2.0, 3.0);
+    let r = EulerAngles {
+        heading: 0.7,
+        pitch: -0.4,
+        bank: 1.2,
+    };
+    let s = Vector3::new(2.0, 0.5, 3.0);
+
+    let m = Matrix4x3::from_trs(&t, &r, &s);
+
+    // Scale happens first, along the local axes
+    let p = Vector3::new(1.0, 0.0, 0.0) * &m;
+    let mut rotated = Matrix4x3::identity();
+    rotated.setup_local_to_parent_euler_angles(&t, &r);
+    assert!(p.approx_eq(&(Vector3::new(2.0, 0.0, 0.0) * &rotated), 0.0001));
+
+    let (t2, r2, s2) = m.decompose();
+    assert!(t2.approx_eq(&t, 0.0001));
+    assert!(s2.approx_eq(&s, 0.0001));
+    assert!((r2.heading - r.heading).abs() < 0.0001);
+    assert!((r2.pitch - r.pitch).abs() < 0.0001);
+    assert!((r2.bank - r.bank).abs() < 0.0001);
+}
+
+#[test]
+fn test_decompose_reflection() {
+    let t = Vector3::zero();
+    let r = EulerAngles::identity();
+    let s = Vector3::new(-1.0, 2.0, 2.0);
+
+    let (_, r2, s2) = Matrix4x3::from_trs(&t, &r, &s).decompose();
+    assert!(s2.approx_eq(&s, 0.0001));
+    assert!(r2.heading.abs() < 0.0001);
+    assert!(r2.pitch.abs() < 0.0001);
+    assert!(r2.bank.abs() < 0.0001);
+}
+
+fn assert_finite_angles(e: &EulerAngles) {
+    assert!(e.heading.is_finite() && e.pitch.is_finite() && e.bank.is_finite());
+}
+
+#[test]
+fn test_decompose_zero_scale() {
+    let t = Vector3::new(1.0, -2.0, 3.0);
+    let r = EulerAngles {
+        heading: 0.7,
+        pitch: -0.4,
+        bank: 1.2,
+    };
+
+    // With one flattened axis, the other two still pin down the orientation
+    let s = Vector3::new(2.0, 0.0, 3.0);
+    let m = Matrix4x3::from_trs(&t, &r, &s);
+    let (t2, r2, s2) = m.decompose();
+    assert!(t2.approx_eq(&t, 0.0001));
+    assert!(s2.approx_eq(&s, 0.0001));
+    assert!((r2.heading - r.heading).abs() < 0.0001);
+    assert!((r2.pitch - r.pitch).abs() < 0.0001);
+    assert!((r2.bank - r.bank).abs() < 0.0001);
+
+    // With two or three, the orientation is made up, but still valid and
+    // rebuilds the same matrix
+    for s in [
+        Vector3::new(0.0, 0.0, 3.0),
+        Vector3::new(2.0, 0.0, 0.0),
+        Vector3::zero(),
+    ] {
+        let m = Matrix4x3::from_trs(&t, &r, &s);
+        let (t2, r2, s2) = m.decompose();
+        assert_finite_angles(&r2);
+        assert!(s2.approx_eq(&s, 0.0001));
+        assert!(Matrix4x3::from_trs(&t2, &r2, &s2).approx_eq(&m, 0.0001));
+    }
+}
+
+#[test]
+fn test_decompose_sheared() {
+    let mut m = Matrix4x3::identity();
+    m.setup_shear(Axis::X, 0.5, 0.25);
+
+    let (_, r2, s2) = m.decompose();
+    assert_finite_angles(&r2);
+    assert!((s2.x - m.right().magnitude()).abs() < 0.0001);
+    assert!((s2.y - 1.0).abs() < 0.0001);
+    assert!((s2.z - 1.0).abs() < 0.0001);
+
+    // The skew is lost, but the rotation is a real one and keeps the
+    // direction of the x axis
+    let rot = r2.to_rotation_matrix();
+    assert!(rot.is_valid(0.0001));
+    let x = rot.object_to_inertial(&Vector3::new(1.0, 0.0, 0.0));
+    assert!(x.approx_eq(&m.right().normalized(), 0.0001));
+
+    // Rows that are parallel, so the matrix is singular, are handled too
+    let mut flat = Matrix4x3::identity();
+    flat.m21 = 1.0;
+    flat.m22 = 0.0;
+    let (_, r3, _) = flat.decompose();
+    assert_finite_angles(&r3);
+    assert!(r3.to_rotation_matrix().is_valid(0.0001));
+}
+
+#[test]
+fn test_add_and_scalar_mul() {
+    let a = test_matrix_a();
+    let half = &a * 0.5;
+    assert_eq!(&half + &half, a);
+    assert_eq!(0.5 * &a, half);
+
+    // Blending two different rotations is not a rotation until
+    // it is orthonormalized
+    let b = Matrix4x3::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), 1.5);
+    let mut blend = &(0.5 * &a) + &(0.5 * &b);
+    assert!(!blend.is_orthonormal(0.0001));
+    blend.orthonormalize();
+    assert!(blend.is_orthonormal(0.0001));
+}
+
+#[test]
+fn test_setup_projection_plane() {
+    let mut m = Matrix4x3::identity();
+    m.setup_projection_plane(&Vector3::new(0.0, 1.0, 0.0), 5.0);
+
+    let p = Vector3::new(3.0, -7.0, 2.0) * &m;
+    assert_eq!(p, Vector3::new(3.0, 5.0, 2.0));
+
+    // A tilted plane.  Points end up on the plane, and points already on
+    // it don't move
+    let n = Vector3::new(0.0, 0.6, 0.8);
+    m.setup_projection_plane(&n, 2.0);
+    let p = Vector3::new(1.0, 4.0, -3.0) * &m;
+    assert!((p.dot(&n) - 2.0).abs() < 0.0001);
+    assert!((p * &m).approx_eq(&p, 0.0001));
+}
@@ -0,0 +1,63 @@
+use math_lib_3d::matrix4x3::*;
+use math_lib_3d::matrix4x4::*;
+use math_lib_3d::vector3::*;
+use std::f32::consts::FRAC_PI_2;
+
+#[test]
+fn test_perspective_near_and_far() {
+    let m = Matrix4x4::perspective(FRAC_PI_2, 1.0, 1.0, 100.0);
+
+    let p = m.project_point(&Vector3::new(0.0, 0.0, 1.0));
+    assert!(p.approx_eq(&Vector3::new(0.0, 0.0, -1.0), 0.0001));
+
+    let p = m.project_point(&Vector3::new(0.0, 0.0, 100.0));
+    assert!(p.approx_eq(&Vector3::new(0.0, 0.0, 1.0), 0.0001));
+
+    // With a 90 degree field of view, the edge of the view is at x = z
+    let p = m.project_point(&Vector3::new(10.0, -5.0, 10.0));
+    assert!((p.x - 1.0).abs() < 0.0001);
+    assert!((p.y + 0.5).abs() < 0.0001);
+
+    // w is the camera space depth
+    let [_, _, _, w] = m.transform(&Vector3::new(3.0, 4.0, 50.0));
+    assert_eq!(w, 50.0);
+}
+
+#[test]
+fn test_perspective_aspect() {
+    // A wide window sees less vertically, so y is scaled up
+    let m = Matrix4x4::perspective(FRAC_PI_2, 2.0, 1.0, 100.0);
+    let p = m.project_point(&Vector3::new(5.0, 5.0, 10.0));
+    assert!((p.x - 0.5).abs() < 0.0001);
+    assert!((p.y - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_orthographic_near_and_far() {
+    let m = Matrix4x4::orthographic(20.0, 10.0, 2.0, 12.0);
+
+    let p = m.project_point(&Vector3::new(10.0, 5.0, 2.0));
+    assert!(p.approx_eq(&Vector3::new(1.0, 1.0, -1.0), 0.0001));
+
+    let p = m.project_point(&Vector3::new(-10.0, 0.0, 12.0));
+    assert!(p.approx_eq(&Vector3::new(-1.0, 0.0, 1.0), 0.0001));
+}
+
+#[test]
+fn test_mul_with_matrix4x3() {
+    let mut model_to_camera = Matrix4x3::identity();
+    model_to_camera.setup_translation(&Vector3::new(0.0, 0.0, 10.0));
+    let clip = Matrix4x4::perspective(FRAC_PI_2, 1.0, 1.0, 100.0);
+
+    let model_to_clip = &model_to_camera * &clip;
+
+    let p = Vector3::new(1.0, 2.0, 5.0);
+    let expected = clip.project_point(&(p * &model_to_camera));
+    assert!(model_to_clip.project_point(&p).approx_eq(&expected, 0.0001));
+
+    // Promoting the identity gives the identity
+    assert_eq!(
+        Matrix4x4::from(&Matrix4x3::identity()),
+        Matrix4x4::identity()
+    );
+}
@@ -0,0 +1,98 @@
+use math_lib_3d::aabb3::AABB3;
+use math_lib_3d::plane::Plane;
+use math_lib_3d::vector3::Vector3;
+
+// The plane y = 1, facing up
+fn floor() -> Plane {
+    Plane::from_triangle(
+        &Vector3::new(0.0, 1.0, 0.0),
+        &Vector3::new(0.0, 1.0, 1.0),
+        &Vector3::new(1.0, 1.0, 0.0),
+    )
+}
+
+#[test]
+fn test_from_triangle() {
+    let p = floor();
+    assert!(p.normal.approx_eq(&Vector3::new(0.0, 1.0, 0.0), 0.000001));
+    assert!((p.d - 1.0).abs() < 0.000001);
+
+    // Listing the vertices the other way around flips the plane
+    let back = Plane::from_triangle(
+        &Vector3::new(0.0, 1.0, 0.0),
+        &Vector3::new(1.0, 1.0, 0.0),
+        &Vector3::new(0.0, 1.0, 1.0),
+    );
+    assert!(back
+        .normal
+        .approx_eq(&Vector3::new(0.0, -1.0, 0.0), 0.000001));
+    assert!((back.d + 1.0).abs() < 0.000001);
+
+    // A tilted triangle contains all three of its vertices
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(-2.0, 0.5, 1.0);
+    let c = Vector3::new(0.0, -1.0, 4.0);
+    let p = Plane::from_triangle(&a, &b, &c);
+    assert!(p.normal.is_unit(0.00001));
+    for v in [a, b, c] {
+        assert!(p.distance_to(&v).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn test_from_point_normal() {
+    let p = Plane::from_point_normal(&Vector3::new(5.0, 1.0, -3.0), &Vector3::new(0.0, 2.0, 0.0));
+    assert_eq!(p, floor());
+}
+
+#[test]
+fn test_distance_and_classify_point() {
+    let p = floor();
+
+    assert!((p.distance_to(&Vector3::new(3.0, 4.0, -1.0)) - 3.0).abs() < 0.000001);
+    assert!((p.distance_to(&Vector3::new(3.0, -1.0, -1.0)) + 2.0).abs() < 0.000001);
+
+    assert_eq!(p.classify_point(&Vector3::new(0.0, 1.5, 0.0), 0.001), 1);
+    assert_eq!(p.classify_point(&Vector3::new(0.0, 0.5, 0.0), 0.001), -1);
+    assert_eq!(p.classify_point(&Vector3::new(7.0, 1.0, -2.0), 0.001), 0);
+    assert_eq!(p.classify_point(&Vector3::new(7.0, 1.0005, -2.0), 0.001), 0);
+}
+
+#[test]
+fn test_classify_aabb() {
+    let p = floor();
+
+    let above = AABB3::from_points(&[Vector3::new(0.0, 2.0, 0.0), Vector3::new(1.0, 3.0, 1.0)]);
+    let across = AABB3::from_points(&[Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 3.0, 1.0)]);
+    let below = AABB3::from_points(&[Vector3::new(0.0, -2.0, 0.0), Vector3::new(1.0, 0.0, 1.0)]);
+
+    assert_eq!(p.classify_aabb(&above), 1);
+    assert_eq!(p.classify_aabb(&across), 0);
+    assert_eq!(p.classify_aabb(&below), -1);
+}
+
+#[test]
+fn test_ray_intersect() {
+    let p = floor();
+
+    // Straight down from above
+    let t = p.ray_intersect(&Vector3::new(0.0, 5.0, 0.0), &Vector3::new(0.0, -2.0, 0.0));
+    assert_eq!(t, Some(2.0));
+
+    // Up from below hits the back side
+    let org = Vector3::new(1.0, -1.0, 0.0);
+    let dir = Vector3::new(0.0, 1.0, 1.0);
+    let t = p.ray_intersect(&org, &dir).unwrap();
+    assert!((t - 2.0).abs() < 0.000001);
+    assert_eq!(p.classify_point(&(org + dir * t), 0.0001), 0);
+
+    // Pointing away, or parallel
+    assert_eq!(
+        p.ray_intersect(&Vector3::new(0.0, 5.0, 0.0), &Vector3::new(0.0, 1.0, 0.0)),
+        None
+    );
+    assert_eq!(
+        p.ray_intersect(&Vector3::new(0.0, 5.0, 0.0), &Vector3::new(1.0, 0.0, 0.0)),
+        None
+    );
+}
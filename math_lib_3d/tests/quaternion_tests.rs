@@ -0,0 +1,477 @@
+use math_lib_3d::euler_angles::EulerAngles;
+use math_lib_3d::quaternion::*;
+use math_lib_3d::rotation_matrix::RotationMatrix;
+use math_lib_3d::vector3::*;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+fn assert_quat_near(a: &Quaternion, b: &Quaternion, epsilon: f32) {
+    assert!(
+        (a.w - b.w).abs() < epsilon
+            && (a.x - b.x).abs() < epsilon
+            && (a.y - b.y).abs() < epsilon
+            && (a.z - b.z).abs() < epsilon,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+fn test_quat_a() -> Quaternion {
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_about_axis(Vector3::new(0.0, 0.6, 0.8), 1.1);
+    q
+}
+
+fn test_quat_b() -> Quaternion {
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_about_axis(Vector3::new(0.8, 0.0, -0.6), -0.4);
+    q
+}
+
+#[test]
+fn test_mul_assign_matches_mul() {
+    let expected = test_quat_a() * test_quat_b();
+
+    let mut q = test_quat_a();
+    q *= test_quat_b();
+
+    assert_quat_near(&q, &expected, 0.000001);
+}
+
+#[test]
+fn test_magnitude() {
+    let q = Quaternion {
+        w: 1.0,
+        x: 2.0,
+        y: 2.0,
+        z: 4.0,
+    };
+    assert_eq!(q.magnitude_squared(), 25.0);
+    assert_eq!(q.magnitude(), 5.0);
+    assert!((test_quat_a().magnitude() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_normalize_scaled_identity() {
+    let mut q = Quaternion {
+        w: 3.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let n = q.normalized();
+    assert_quat_near(&n, &Quaternion::identity(), 0.000001);
+
+    // normalized() left the original alone, and normalize() returns
+    // the old length
+    assert_eq!(q.w, 3.0);
+    assert_eq!(q.normalize(), 3.0);
+    assert_quat_near(&q, &Quaternion::identity(), 0.000001);
+}
+
+#[test]
+fn test_normalize_zero() {
+    let mut q = Quaternion {
+        w: 0.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    assert_eq!(q.normalize(), 0.0);
+    assert_quat_near(&q, &Quaternion::identity(), 0.000001);
+
+    let zero = Quaternion {
+        w: 0.0,
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    assert_quat_near(&zero.normalized(), &Quaternion::identity(), 0.000001);
+}
+
+#[test]
+fn test_log_exp_round_trip() {
+    for q in [test_quat_a(), test_quat_b(), Quaternion::identity()].iter() {
+        let l = q.log();
+        assert_eq!(l.w, 0.0);
+        assert_quat_near(&l.exp(), q, 0.0001);
+    }
+
+    // The log of a rotation is half the angle times the axis
+    let l = test_quat_a().log();
+    assert!((l.y - 0.6 * 0.55).abs() < 0.0001);
+    assert!((l.z - 0.8 * 0.55).abs() < 0.0001);
+}
+
+#[test]
+fn test_pow_scales_angle() {
+    let axis = Vector3::new(0.0, 0.6, 0.8);
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_about_axis(axis, 1.2);
+
+    let mut expected = Quaternion::identity();
+    expected.set_to_rotate_about_axis(axis, 0.3);
+    assert_quat_near(&pow(q, 0.25), &expected, 0.0001);
+
+    expected.set_to_rotate_about_axis(axis, 2.4);
+    assert_quat_near(&pow(q, 2.0), &expected, 0.0001);
+
+    // Identity is unchanged
+    assert_quat_near(
+        &pow(Quaternion::identity(), 3.0),
+        &Quaternion::identity(),
+        0.000001,
+    );
+}
+
+#[test]
+fn test_difference() {
+    let q = test_quat_a();
+    assert_quat_near(&difference(&Quaternion::identity(), &q), &q, 0.000001);
+
+    let from = test_quat_b();
+    let to = test_quat_a();
+    let d = difference(&from, &to);
+    assert_quat_near(&(from * d), &to, 0.0001);
+
+    // No difference between an orientation and itself
+    assert_quat_near(&difference(&to, &to), &Quaternion::identity(), 0.0001);
+}
+
+#[test]
+fn test_squad() {
+    let axis = Vector3::new(0.0, 1.0, 0.0);
+    let keys: Vec<Quaternion> = [0.0, 0.5, 1.5, 2.0]
+        .iter()
+        .map(|&theta| {
+            let mut q = Quaternion::identity();
+            q.set_to_rotate_about_axis(axis, theta);
+            q
+        })
+        .collect();
+
+    let q = squad(&keys[0], &keys[1], &keys[2], &keys[3], 0.0);
+    assert_quat_near(&q, &keys[1], 0.0001);
+
+    let q = squad(&keys[0], &keys[1], &keys[2], &keys[3], 1.0);
+    assert_quat_near(&q, &keys[2], 0.0001);
+
+    let q = squad(&keys[0], &keys[1], &keys[2], &keys[3], 0.5);
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+
+    // All keys rotate about the same axis, so the curve does too
+    assert!(q.get_rotation_axis().approx_eq(&axis, 0.0001));
+}
+
+#[test]
+fn test_squad_negative_control_dot() {
+    // Keys chosen so the two control points land in opposite hemispheres.
+    // Squad must not flip the sign of either inner slerp partway through
+    // the segment, or the curve jumps.
+    let rot_z = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 2.0);
+    let rot_x = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), 2.0);
+    let keys = [
+        Quaternion::identity(),
+        rot_z,
+        rot_z * rot_x,
+        Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 2.0),
+    ];
+    let s1 = intermediate(&keys[0], &keys[1], &keys[2]);
+    let s2 = intermediate(&keys[1], &keys[2], &keys[3]);
+    assert!(dot_product(&s1, &s2) < 0.0);
+
+    let q = squad(&keys[0], &keys[1], &keys[2], &keys[3], 0.0);
+    assert_quat_near(&q, &keys[1], 0.0001);
+    let q = squad(&keys[0], &keys[1], &keys[2], &keys[3], 1.0);
+    assert_quat_near(&q, &keys[2], 0.0001);
+
+    let mut prev = keys[1];
+    for i in 1..=1000 {
+        let q = squad(&keys[0], &keys[1], &keys[2], &keys[3], i as f32 / 1000.0);
+        assert!(dot_product(&prev, &q).abs() > 0.999, "jump at step {}", i);
+        prev = q;
+    }
+}
+
+#[test]
+fn test_slerp_no_invert_takes_long_arc() {
+    let q0 = Quaternion::identity();
+    let q1 = -Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.5);
+    assert!(dot_product(&q0, &q1) < 0.0);
+
+    // slerp negates q1 and stays close to q0; slerp_no_invert goes the
+    // long way round to q1 itself
+    let q = slerp_no_invert(&q0, &q1, 1.0);
+    assert_quat_near(&q, &q1, 0.0001);
+    let q = slerp_no_invert(&q0, &q1, 0.5);
+    assert!(dot_product(&q0, &q) < dot_product(&q0, &slerp(&q0, &q1, 0.5)));
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+}
+
+#[test]
+fn test_slerp_no_invert_opposite() {
+    // There is no unique arc from q to -q, but every point along the way
+    // must still be a unit quaternion
+    let q0 = test_quat_a();
+    let q1 = -q0;
+
+    let q = slerp_no_invert(&q0, &q1, 0.5);
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+    assert!(dot_product(&q0, &q).abs() < 0.0001);
+
+    let q = slerp_no_invert(&q0, &q1, 0.999);
+    assert!(dot_product(&q1, &q) > 0.999);
+}
+
+#[test]
+fn test_intermediate_evenly_spaced() {
+    // For keys evenly spaced about one axis, the control point is the
+    // key itself
+    let axis = Vector3::new(0.6, 0.0, 0.8);
+    let mut prev = Quaternion::identity();
+    let mut cur = Quaternion::identity();
+    let mut next = Quaternion::identity();
+    prev.set_to_rotate_about_axis(axis, 0.2);
+    cur.set_to_rotate_about_axis(axis, 0.7);
+    next.set_to_rotate_about_axis(axis, 1.2);
+
+    assert_quat_near(&intermediate(&prev, &cur, &next), &cur, 0.0001);
+}
+
+#[test]
+fn test_to_rotation_matrix() {
+    let q = test_quat_a();
+
+    let mut expected = RotationMatrix::identity();
+    expected.set_from_inertial_to_object_quaternion(&q);
+
+    assert_eq!(q.to_rotation_matrix(), expected);
+}
+
+#[test]
+fn test_to_euler_inertial_to_object() {
+    let q = test_quat_b();
+
+    let expected = EulerAngles::from_inertial_to_object_quaternion(&q);
+    let e = q.to_euler_inertial_to_object();
+
+    assert_eq!(e.heading, expected.heading);
+    assert_eq!(e.pitch, expected.pitch);
+    assert_eq!(e.bank, expected.bank);
+}
+
+#[test]
+fn test_get_rotation_angle() {
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_about_axis(Vector3::new(0.0, 0.6, 0.8), 1.1);
+    assert!((q.get_rotation_angle() - 1.1).abs() < 0.0001);
+
+    assert_eq!(Quaternion::identity().get_rotation_angle(), 0.0);
+}
+
+#[test]
+fn test_neg() {
+    let q = test_quat_a();
+    let n = -&q;
+
+    assert_eq!(n.w, -q.w);
+    assert_eq!(n.x, -q.x);
+    assert_eq!(n.y, -q.y);
+    assert_eq!(n.z, -q.z);
+
+    // Same rotation, so the same matrix
+    let m = q.to_rotation_matrix();
+    let mn = (-q).to_rotation_matrix();
+    assert!((m.m12 - mn.m12).abs() < 0.000001);
+    assert!((m.m23 - mn.m23).abs() < 0.000001);
+    assert!((m.m31 - mn.m31).abs() < 0.000001);
+}
+
+#[test]
+fn test_approx_eq() {
+    let q = test_quat_a();
+
+    assert!(q.approx_eq(&q, 0.0));
+    assert!(q.approx_eq(&-&q, 0.0));
+    assert!((-&q).approx_eq(&q, 0.0));
+    assert!(!q.approx_eq(&test_quat_b(), 0.001));
+
+    let mut nudged = q;
+    nudged.x += 0.0005;
+    assert!(q.approx_eq(&nudged, 0.001));
+    assert!(!q.approx_eq(&nudged, 0.0001));
+}
+
+#[test]
+fn test_euler_round_trip_approx_eq() {
+    // The quaternion that comes back from Euler angles may be the
+    // negation of the one we started with
+    let q = -test_quat_a();
+
+    let mut round_trip = Quaternion::identity();
+    round_trip.set_to_rotate_inertial_to_object(q.to_euler_inertial_to_object());
+
+    assert!(round_trip.approx_eq(&q, 0.0001));
+}
+
+#[test]
+fn test_from_axis_angle() {
+    let axis = Vector3::new(0.0, 0.6, 0.8);
+    let q = Quaternion::from_axis_angle(&axis, 1.1);
+    assert_quat_near(&q, &test_quat_a(), 0.000001);
+}
+
+#[test]
+fn test_axis_angle_round_trip() {
+    let axis = Vector3::new(0.8, 0.0, -0.6);
+    let (out_axis, out_theta) = Quaternion::from_axis_angle(&axis, 2.5).to_axis_angle();
+
+    assert!(out_axis.approx_eq(&axis, 0.0001));
+    assert!((out_theta - 2.5).abs() < 0.0001);
+
+    // The identity has no meaningful axis, but the angle must be zero
+    // and the axis must still be usable
+    let (out_axis, out_theta) = Quaternion::identity().to_axis_angle();
+    assert_eq!(out_theta, 0.0);
+    assert!((out_axis.magnitude() - 1.0).abs() < 0.0001);
+
+    let q = Quaternion::from_axis_angle(&out_axis, out_theta);
+    assert_quat_near(&q, &Quaternion::identity(), 0.000001);
+}
+
+#[test]
+fn test_from_to_rotation() {
+    let from = Vector3::new(1.0, 0.0, 0.0);
+    let to = Vector3::new(0.0, 1.0, 0.0);
+
+    let q = Quaternion::from_to_rotation(&from, &to);
+    let expected = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+    assert!(q.approx_eq(&expected, 0.0001));
+
+    // Lengths don't matter, only directions
+    let from = Vector3::new(0.0, 3.0, 4.0);
+    let to = Vector3::new(-2.0, 0.5, 1.0);
+    let (axis, theta) = Quaternion::from_to_rotation(&from, &to).to_axis_angle();
+    let rotated = from.normalized().rotate_about_axis(&axis, theta);
+    assert!(rotated.approx_eq(&to.normalized(), 0.0001));
+}
+
+#[test]
+fn test_from_to_rotation_antiparallel() {
+    let from = Vector3::new(1.0, 0.0, 0.0);
+    let to = Vector3::new(-1.0, 0.0, 0.0);
+
+    let q = Quaternion::from_to_rotation(&from, &to);
+    assert!((q.magnitude() - 1.0).abs() < 0.0001);
+
+    let (axis, theta) = q.to_axis_angle();
+    assert!((theta - PI).abs() < 0.0001);
+    assert!(axis.dot(&from).abs() < 0.0001);
+    assert!(from.rotate_about_axis(&axis, theta).approx_eq(&to, 0.0001));
+}
+
+#[test]
+fn test_from_to_rotation_parallel() {
+    let v = Vector3::new(0.0, 0.6, 0.8);
+    let q = Quaternion::from_to_rotation(&v, &v);
+    assert!(q.approx_eq(&Quaternion::identity(), 0.000001));
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_mul_by_reference() {
+    let steps = [
+        test_quat_a(),
+        test_quat_b(),
+        Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), 0.3),
+    ];
+
+    let mut total = Quaternion::identity();
+    for step in &steps {
+        total = &total * step;
+    }
+
+    let expected = steps[0] * steps[1] * steps[2];
+    assert_quat_near(&total, &expected, 0.000001);
+    assert_quat_near(&(&steps[0] * &steps[1]), &(steps[0] * steps[1]), 0.000001);
+}
+
+fn rotate(v: &Vector3, q: &Quaternion) -> Vector3 {
+    let (axis, theta) = q.to_axis_angle();
+    v.rotate_about_axis(&axis, theta)
+}
+
+#[test]
+fn test_concatenate_order() {
+    let a = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+    let b = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), FRAC_PI_2);
+    let v = Vector3::new(1.0, 0.0, 0.0);
+
+    // x -> y about z, then y -> z about x
+    let expected = rotate(&rotate(&v, &a), &b);
+    assert!(expected.approx_eq(&Vector3::new(0.0, 0.0, 1.0), 0.0001));
+    assert!(rotate(&v, &a.concatenate(&b)).approx_eq(&expected, 0.0001));
+
+    // The other order gives a different answer
+    assert!(!rotate(&v, &b.concatenate(&a)).approx_eq(&expected, 0.0001));
+
+    let v = Vector3::new(0.3, -1.2, 2.0);
+    let (a, b) = (test_quat_a(), test_quat_b());
+    let expected = rotate(&rotate(&v, &a), &b);
+    assert!(rotate(&v, &a.concatenate(&b)).approx_eq(&expected, 0.0001));
+}
+
+#[test]
+fn test_is_identity() {
+    assert!(Quaternion::identity().is_identity(0.0));
+    assert!((-Quaternion::identity()).is_identity(0.0));
+
+    let tiny = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.0001);
+    assert!(tiny.is_identity(0.001));
+    assert!(!tiny.is_identity(0.00001));
+    assert!(!test_quat_a().is_identity(0.001));
+}
+
+#[test]
+fn test_canonical() {
+    for q in [test_quat_a(), -test_quat_a(), test_quat_b(), -test_quat_b()] {
+        let c = q.canonical();
+        assert!(c.w >= 0.0);
+        assert!(c.approx_eq(&q, 0.0));
+        assert_quat_near(&c, &(-q).canonical(), 0.000001);
+    }
+
+    // w of zero (a 180 degree rotation) is still made unique
+    let half_turn = Quaternion {
+        w: 0.0,
+        x: 0.0,
+        y: -0.6,
+        z: 0.8,
+    };
+    assert_quat_near(&half_turn.canonical(), &(-half_turn).canonical(), 0.000001);
+    assert!(half_turn.canonical().y > 0.0);
+}
+
+#[test]
+fn test_slerp_weights() {
+    let q0 = test_quat_a();
+    let near = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.6, 0.8), 1.1001);
+
+    // -test_quat_b() takes the short arc through -q1, so k1 comes back
+    // negated
+    for q1 in [test_quat_b(), -test_quat_b(), near] {
+        for t in [0.0, 0.25, 0.5, 0.9, 1.0] {
+            let (k0, k1) = slerp_weights(&q0, &q1, t);
+            let blended = Quaternion {
+                w: k0 * q0.w + k1 * q1.w,
+                x: k0 * q0.x + k1 * q1.x,
+                y: k0 * q0.y + k1 * q1.y,
+                z: k0 * q0.z + k1 * q1.z,
+            };
+            assert_quat_near(&blended, &slerp(&q0, &q1, t), 0.000001);
+        }
+    }
+
+    assert_eq!(slerp_weights(&q0, &test_quat_b(), 0.0), (1.0, 0.0));
+    assert_eq!(slerp_weights(&q0, &test_quat_b(), 1.0), (0.0, 1.0));
+}
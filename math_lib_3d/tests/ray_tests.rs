@@ -0,0 +1,73 @@
+use math_lib_3d::aabb3::AABB3;
+use math_lib_3d::ray::Ray;
+use math_lib_3d::vector3::Vector3;
+
+fn unit_cube() -> AABB3 {
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(0.0, 0.0, 0.0));
+    b.add_vector3(&Vector3::new(1.0, 1.0, 1.0));
+    b
+}
+
+#[test]
+fn test_point_at() {
+    let r = Ray::new(Vector3::new(1.0, 2.0, 3.0), Vector3::new(2.0, 0.0, -4.0));
+    assert_eq!(r.point_at(0.0), r.origin);
+    assert_eq!(r.point_at(1.0), Vector3::new(3.0, 2.0, -1.0));
+    assert_eq!(r.point_at(0.5), Vector3::new(2.0, 2.0, 1.0));
+}
+
+// These are the same cases as the ray_intersect tests in aabb3_tests.rs
+
+#[test]
+fn test_intersect_aabb_hit() {
+    let b = unit_cube();
+
+    // Hit the -x face halfway along the ray
+    let r = Ray::new(Vector3::new(-2.0, 0.5, 0.5), Vector3::new(4.0, 0.0, 0.0));
+    let (t, normal) = r.intersect_aabb(&b).unwrap();
+    assert!((t - 0.5).abs() < 0.000001);
+    assert_eq!(normal, Vector3::new(-1.0, 0.0, 0.0));
+    assert!(r
+        .point_at(t)
+        .approx_eq(&Vector3::new(0.0, 0.5, 0.5), 0.000001));
+
+    // Coming down onto the top face at an angle
+    let r = Ray::new(Vector3::new(0.0, 3.0, 0.5), Vector3::new(1.0, -4.0, 0.0));
+    let (t, normal) = r.intersect_aabb(&b).unwrap();
+    assert!((t - 0.5).abs() < 0.000001);
+    assert_eq!(normal, Vector3::new(0.0, 1.0, 0.0));
+
+    let r = Ray::new(Vector3::new(0.5, 0.5, 5.0), Vector3::new(0.0, 0.0, -10.0));
+    let (t, normal) = r.intersect_aabb(&b).unwrap();
+    assert!((t - 0.4).abs() < 0.000001);
+    assert_eq!(normal, Vector3::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn test_intersect_aabb_miss() {
+    let b = unit_cube();
+
+    // Pointing away
+    let r = Ray::new(Vector3::new(-2.0, 0.5, 0.5), Vector3::new(-4.0, 0.0, 0.0));
+    assert_eq!(r.intersect_aabb(&b), None);
+
+    // Passing beside the box
+    let r = Ray::new(Vector3::new(-2.0, 1.5, 0.5), Vector3::new(4.0, 0.0, 0.0));
+    assert_eq!(r.intersect_aabb(&b), None);
+
+    // Too short to reach it
+    let r = Ray::new(Vector3::new(-2.0, 0.5, 0.5), Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(r.intersect_aabb(&b), None);
+}
+
+#[test]
+fn test_intersect_aabb_inside() {
+    let b = unit_cube();
+
+    let r = Ray::new(Vector3::new(0.5, 0.5, 0.5), Vector3::new(0.0, 2.0, 0.0));
+    assert_eq!(
+        r.intersect_aabb(&b),
+        Some((0.0, Vector3::new(0.0, -1.0, 0.0)))
+    );
+}
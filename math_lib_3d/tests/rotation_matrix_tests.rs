@@ -0,0 +1,264 @@
+use math_lib_3d::euler_angles::EulerAngles;
+use math_lib_3d::matrix4x3::Matrix4x3;
+use math_lib_3d::quaternion::Quaternion;
+use math_lib_3d::rotation_matrix::*;
+use math_lib_3d::vector3::*;
+use std::f32::consts::FRAC_PI_2;
+
+#[test]
+fn test_orthonormalize() {
+    let mut m = RotationMatrix::identity();
+    m.m11 = 0.98;
+    m.m13 = 0.02;
+    m.m21 = 0.03;
+    m.m22 = 1.01;
+    m.m31 = -0.04;
+    m.m32 = 0.02;
+
+    m.orthonormalize();
+
+    let r1 = Vector3::new(m.m11, m.m12, m.m13);
+    let r2 = Vector3::new(m.m21, m.m22, m.m23);
+    let r3 = Vector3::new(m.m31, m.m32, m.m33);
+    assert!(r1.is_unit(0.0001));
+    assert!(r2.is_unit(0.0001));
+    assert!(r3.is_unit(0.0001));
+    assert!(r1.dot(&r2).abs() < 0.0001);
+    assert!(r1.dot(&r3).abs() < 0.0001);
+    assert!(r2.dot(&r3).abs() < 0.0001);
+}
+
+#[test]
+fn test_display_grid() {
+    let m = RotationMatrix::identity();
+    let expected = "\
+|    1.0000    0.0000    0.0000 |
+|    0.0000    1.0000    0.0000 |
+|    0.0000    0.0000    1.0000 |";
+    assert_eq!(format!("{}", m), expected);
+}
+
+#[test]
+fn test_copy_is_independent() {
+    let orientation = EulerAngles {
+        heading: 0.3,
+        pitch: -0.2,
+        bank: 0.9,
+    };
+    let original = RotationMatrix::from_euler_angles(&orientation);
+
+    let mut copy = original;
+    assert_eq!(copy, original);
+
+    copy.m12 = 5.0;
+    copy.orthonormalize();
+    assert_ne!(copy, original);
+    assert_eq!(original, RotationMatrix::from_euler_angles(&orientation));
+}
+
+#[test]
+fn test_transpose_swaps_direction() {
+    let m = RotationMatrix::from_euler_angles(&EulerAngles {
+        heading: 1.2,
+        pitch: 0.4,
+        bank: -0.7,
+    });
+    let v = Vector3::new(0.5, -2.0, 3.0);
+
+    assert!(m
+        .transpose()
+        .inertial_to_object(&v)
+        .approx_eq(&m.object_to_inertial(&v), 0.00001));
+    assert!(m
+        .transpose()
+        .object_to_inertial(&v)
+        .approx_eq(&m.inertial_to_object(&v), 0.00001));
+
+    assert_eq!(m.transpose().transpose(), m);
+    assert_eq!(m.inverse(), m.transpose());
+
+    // Going there and back again is the identity
+    let there = m.inertial_to_object(&v);
+    assert!(m.inverse().inertial_to_object(&there).approx_eq(&v, 0.0001));
+}
+
+fn assert_rotation_near(a: &RotationMatrix, b: &RotationMatrix, epsilon: f32) {
+    let a_rows = [
+        a.m11, a.m12, a.m13, a.m21, a.m22, a.m23, a.m31, a.m32, a.m33,
+    ];
+    let b_rows = [
+        b.m11, b.m12, b.m13, b.m21, b.m22, b.m23, b.m31, b.m32, b.m33,
+    ];
+    for (x, y) in a_rows.iter().zip(b_rows.iter()) {
+        assert!((x - y).abs() < epsilon, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_from_quaternion_constructors() {
+    let q = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.6, 0.8), 1.1);
+
+    let mut expected = RotationMatrix::identity();
+    expected.set_from_inertial_to_object_quaternion(&q);
+    assert_eq!(
+        RotationMatrix::from_inertial_to_object_quaternion(&q),
+        expected
+    );
+
+    expected.set_from_object_to_inertial_quaternion(&q);
+    assert_eq!(
+        RotationMatrix::from_object_to_inertial_quaternion(&q),
+        expected
+    );
+}
+
+#[test]
+fn test_from_axis_angle() {
+    let axis = Vector3::new(0.8, 0.0, -0.6);
+    let m = RotationMatrix::from_axis_angle(&axis, 0.9);
+
+    // Same as the 4x3 version, without the translation
+    let m43 = Matrix4x3::from_axis_angle(&axis, 0.9);
+    let expected = RotationMatrix {
+        m11: m43.m11,
+        m12: m43.m12,
+        m13: m43.m13,
+        m21: m43.m21,
+        m22: m43.m22,
+        m23: m43.m23,
+        m31: m43.m31,
+        m32: m43.m32,
+        m33: m43.m33,
+    };
+    assert_eq!(m, expected);
+
+    // And the same as going through a quaternion
+    let q = Quaternion::from_axis_angle(&axis, 0.9);
+    assert_rotation_near(
+        &m,
+        &RotationMatrix::from_inertial_to_object_quaternion(&q),
+        0.0001,
+    );
+
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert!(m
+        .inertial_to_object(&v)
+        .approx_eq(&v.rotate_about_axis(&axis, 0.9), 0.0001));
+}
+
+#[test]
+fn test_is_valid() {
+    assert!(RotationMatrix::identity().is_valid(0.0001));
+
+    let m = RotationMatrix::from_euler_angles(&EulerAngles {
+        heading: 1.2,
+        pitch: 0.4,
+        bank: -0.7,
+    });
+    assert!(m.is_valid(0.0001));
+
+    // Skew one row toward another
+    let mut skewed = m;
+    skewed.m21 += 0.1 * m.m11;
+    skewed.m22 += 0.1 * m.m12;
+    skewed.m23 += 0.1 * m.m13;
+    assert!(!skewed.is_valid(0.0001));
+
+    // Scaled
+    let mut scaled = RotationMatrix::identity();
+    scaled.m22 = 1.5;
+    assert!(!scaled.is_valid(0.0001));
+
+    // Orthonormal, but a reflection
+    let mut mirrored = RotationMatrix::identity();
+    mirrored.m33 = -1.0;
+    assert!(!mirrored.is_valid(0.0001));
+}
+
+#[test]
+fn test_setup_matches_from_euler_angles() {
+    let orientations = [
+        (0.0, 0.0, 0.0),
+        (1.2, 0.4, -0.7),
+        (-2.5, -1.1, 3.0),
+        (0.3, FRAC_PI_2, 0.2),
+    ];
+
+    for (heading, pitch, bank) in orientations {
+        let orientation = EulerAngles {
+            heading,
+            pitch,
+            bank,
+        };
+
+        let mut m = RotationMatrix::identity();
+        m.setup(&orientation);
+        assert_eq!(m, RotationMatrix::from_euler_angles(&orientation));
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_mul_applies_left_then_right() {
+    let base = RotationMatrix::from_euler_angles(&EulerAngles {
+        heading: 1.2,
+        pitch: 0.4,
+        bank: -0.7,
+    });
+    let delta = RotationMatrix::from_axis_angle(&Vector3::new(0.0, 0.6, 0.8), 0.5);
+    let v = Vector3::new(0.5, -2.0, 3.0);
+
+    let expected = delta.inertial_to_object(&base.inertial_to_object(&v));
+    assert!((base * delta)
+        .inertial_to_object(&v)
+        .approx_eq(&expected, 0.0001));
+    assert_eq!(base * delta, &base * &delta);
+
+    // The product is still a rotation
+    assert!((base * delta).is_valid(0.0001));
+
+    // And a rotation times its inverse is the identity
+    assert_rotation_near(
+        &(base * base.inverse()),
+        &RotationMatrix::identity(),
+        0.0001,
+    );
+
+    // Consistent with quaternion concatenation
+    let qa = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), 0.3);
+    let qb = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), -1.1);
+    assert_rotation_near(
+        &(qa.to_rotation_matrix() * qb.to_rotation_matrix()),
+        &(qa * qb).to_rotation_matrix(),
+        0.0001,
+    );
+}
+
+#[test]
+fn test_rotate_slice() {
+    let m = RotationMatrix::from_euler_angles(&EulerAngles {
+        heading: 1.2,
+        pitch: 0.4,
+        bank: -0.7,
+    });
+    let normals = [
+        Vector3::new(1.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.6, 0.8),
+        Vector3::new(-0.48, 0.8, 0.36),
+    ];
+
+    let mut rotated = normals;
+    m.inertial_to_object_slice(&mut rotated);
+    for (r, n) in rotated.iter().zip(normals.iter()) {
+        assert_eq!(*r, m.inertial_to_object(n));
+    }
+
+    let mut rotated = normals;
+    m.object_to_inertial_slice(&mut rotated);
+    for (r, n) in rotated.iter().zip(normals.iter()) {
+        assert_eq!(*r, m.object_to_inertial(n));
+    }
+
+    // Empty lists are fine
+    m.object_to_inertial_slice(&mut []);
+}
@@ -0,0 +1,49 @@
+use math_lib_3d::s3d_handler::import_s3d;
+use std::fs;
+use std::path::PathBuf;
+
+// Write an S3D file with one part, one texture and three vertices, using
+// the given triangle lines
+fn write_s3d(name: &str, tris: &[&str]) -> PathBuf {
+    let mut text = String::new();
+    text.push_str("// version\n103\n");
+    text.push_str("// numTextures,numTris,numVerts,numParts,numFrames,numLights,numCameras\n");
+    text.push_str(&format!("1,{},3,1,0,0,0\n", tris.len()));
+    text.push_str("// partList: firstVert,numVerts,firstTri,numTris,\"name\"\n");
+    text.push_str(&format!("0,3,0,{},\"part\"\n", tris.len()));
+    text.push_str("// texture list: name\ntex.tga\n");
+    text.push_str("// triList: materialIndex,vertices(index, texX, texY)\n");
+    for tri in tris {
+        text.push_str(tri);
+        text.push('\n');
+    }
+    text.push_str("// vertList: x,y,z\n0, 0, 0\n1, 0, 0\n0, 1, 0\n");
+
+    let path = std::env::temp_dir().join(format!("math_lib_3d_{}.s3d", name));
+    fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn test_import_degenerate_tri() {
+    // Degenerate triangles still load, and can be cleaned up afterwards
+    let path = write_s3d(
+        "degenerate",
+        &["0, 0,0,0, 1,256,0, 2,0,256", "0, 0,0,0, 1,256,0, 1,0,256"],
+    );
+    let mut mesh = import_s3d(path.to_str().unwrap()).unwrap();
+    assert_eq!(mesh.triCount(), 2);
+    assert!(mesh.index_check().is_ok());
+    assert!(mesh.validity_check().is_err());
+
+    mesh.deleteDegenerateTris();
+    assert_eq!(mesh.triCount(), 1);
+    assert_eq!(mesh.validity_check(), Ok(()));
+}
+
+#[test]
+fn test_import_bad_vertex_index() {
+    let path = write_s3d("bad_index", &["0, 0,0,0, 1,256,0, 3,0,256"]);
+    let err = import_s3d(path.to_str().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("index 3"), "{}", err);
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "serde")]
+
+use math_lib_3d::aabb3::AABB3;
+use math_lib_3d::euler_angles::EulerAngles;
+use math_lib_3d::matrix4x3::Matrix4x3;
+use math_lib_3d::quaternion::Quaternion;
+use math_lib_3d::vector3::Vector3;
+
+fn round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_vector3_round_trip() {
+    let v = Vector3::new(1.5, -2.0, 3.25);
+    assert_eq!(round_trip(&v), v);
+
+    let json = serde_json::to_string(&v).unwrap();
+    assert_eq!(json, r#"{"x":1.5,"y":-2.0,"z":3.25}"#);
+}
+
+#[test]
+fn test_matrix4x3_round_trip() {
+    let mut m = Matrix4x3::identity();
+    m.setup_rotate_axis(2, 0.5);
+    m.set_translation(&Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(round_trip(&m), m);
+
+    let json = serde_json::to_string(&m).unwrap();
+    for field in [
+        "m11", "m12", "m13", "m21", "m22", "m23", "m31", "m32", "m33", "tx", "ty", "tz",
+    ] {
+        assert!(json.contains(&format!("\"{}\":", field)));
+    }
+}
+
+#[test]
+fn test_quaternion_round_trip() {
+    let mut q = Quaternion::identity();
+    q.set_to_rotate_about_y(1.25);
+
+    // Quaternion doesn't implement PartialEq, so compare the Debug output,
+    // which prints every field
+    assert_eq!(format!("{:?}", round_trip(&q)), format!("{:?}", q));
+}
+
+#[test]
+fn test_euler_angles_round_trip() {
+    let e = EulerAngles {
+        heading: 0.5,
+        pitch: -0.25,
+        bank: 1.0,
+    };
+
+    // No PartialEq here either, so compare the Debug output
+    assert_eq!(format!("{:?}", round_trip(&e)), format!("{:?}", e));
+}
+
+#[test]
+fn test_aabb3_round_trip() {
+    let mut b = AABB3::new();
+    b.min = Vector3::new(-1.0, -2.0, -3.0);
+    b.max = Vector3::new(4.0, 5.0, 6.0);
+    assert_eq!(round_trip(&b), b);
+}
@@ -0,0 +1,97 @@
+use math_lib_3d::aabb3::AABB3;
+use math_lib_3d::sphere::Sphere;
+use math_lib_3d::vector3::Vector3;
+
+fn unit_sphere() -> Sphere {
+    Sphere::new(Vector3::zero(), 1.0)
+}
+
+#[test]
+fn test_contains() {
+    let s = unit_sphere();
+    assert!(s.contains(&Vector3::zero()));
+    assert!(s.contains(&Vector3::new(0.5, 0.5, 0.5)));
+    assert!(s.contains(&Vector3::new(0.0, 1.0, 0.0)));
+    assert!(!s.contains(&Vector3::new(0.6, 0.6, 0.6)));
+}
+
+#[test]
+fn test_intersects_sphere() {
+    let s = unit_sphere();
+    assert!(s.intersects_sphere(&Sphere::new(Vector3::new(1.5, 0.0, 0.0), 1.0)));
+    assert!(s.intersects_sphere(&Sphere::new(Vector3::new(0.1, 0.0, 0.0), 0.1)));
+
+    // Touching doesn't count
+    assert!(!s.intersects_sphere(&Sphere::new(Vector3::new(2.0, 0.0, 0.0), 1.0)));
+    assert!(!s.intersects_sphere(&Sphere::new(Vector3::new(0.0, 3.0, 0.0), 1.0)));
+}
+
+#[test]
+fn test_intersects_aabb() {
+    let s = unit_sphere();
+    let mut b = AABB3::new();
+    b.add_vector3(&Vector3::new(0.5, 0.5, 0.5));
+    b.add_vector3(&Vector3::new(2.0, 2.0, 2.0));
+    assert!(s.intersects_aabb(&b));
+
+    let mut far = AABB3::new();
+    far.add_vector3(&Vector3::new(0.7, 0.7, 0.7));
+    far.add_vector3(&Vector3::new(2.0, 2.0, 2.0));
+    assert!(!s.intersects_aabb(&far));
+}
+
+#[test]
+fn test_ray_intersect_hit() {
+    let s = unit_sphere();
+    let t = s
+        .ray_intersect(&Vector3::new(-5.0, 0.0, 0.0), &Vector3::new(1.0, 0.0, 0.0))
+        .unwrap();
+    assert!((t - 4.0).abs() < 0.000001);
+
+    // Direction doesn't need to be unit length.  Treated as a segment, we
+    // enter the sphere 40% of the way along
+    let t = s
+        .ray_intersect(&Vector3::new(0.0, 0.0, -5.0), &Vector3::new(0.0, 0.0, 10.0))
+        .unwrap();
+    assert!((t - 0.4).abs() < 0.000001);
+
+    // Offset from the center, the hit point is on the surface
+    let org = Vector3::new(-5.0, 0.5, 0.0);
+    let dir = Vector3::new(1.0, 0.0, 0.0);
+    let t = s.ray_intersect(&org, &dir).unwrap();
+    let hit = org + dir * t;
+    assert!((hit.magnitude() - 1.0).abs() < 0.0001);
+    assert!(hit.x < 0.0);
+}
+
+#[test]
+fn test_ray_intersect_miss() {
+    let s = unit_sphere();
+
+    // Passing by
+    assert_eq!(
+        s.ray_intersect(&Vector3::new(-5.0, 2.0, 0.0), &Vector3::new(1.0, 0.0, 0.0)),
+        None
+    );
+
+    // Pointing away
+    assert_eq!(
+        s.ray_intersect(&Vector3::new(-5.0, 0.0, 0.0), &Vector3::new(-1.0, 0.0, 0.0)),
+        None
+    );
+
+    // No direction
+    assert_eq!(
+        s.ray_intersect(&Vector3::new(-5.0, 0.0, 0.0), &Vector3::zero()),
+        None
+    );
+}
+
+#[test]
+fn test_ray_intersect_inside() {
+    let s = unit_sphere();
+    assert_eq!(
+        s.ray_intersect(&Vector3::new(0.5, 0.0, 0.0), &Vector3::new(1.0, 0.0, 0.0)),
+        Some(0.0)
+    );
+}
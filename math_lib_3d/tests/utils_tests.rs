@@ -0,0 +1,17 @@
+use math_lib_3d::utils::safe_acos;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+#[test]
+fn test_safe_acos() {
+    assert_eq!(safe_acos(1.0), 0.0);
+    assert_eq!(safe_acos(-1.0), PI);
+    assert!((safe_acos(0.0) - FRAC_PI_2).abs() < 0.000001);
+    assert!((safe_acos(0.5) - 0.5f32.acos()).abs() < 0.000001);
+}
+
+#[test]
+fn test_safe_acos_clamps_out_of_range() {
+    assert_eq!(safe_acos(1.5), 0.0);
+    assert_eq!(safe_acos(-1.5), PI);
+    assert_eq!(safe_acos(1.000001), 0.0);
+}
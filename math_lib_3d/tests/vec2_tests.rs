@@ -0,0 +1,42 @@
+use math_lib_3d::edit_tri_mesh::*;
+use math_lib_3d::vector3::*;
+
+#[test]
+fn test_vec2_arithmetic() {
+    let a = Vec2::new(1.0, 2.0);
+    let b = Vec2::new(3.0, -4.0);
+
+    assert_eq!(&a + &b, Vec2::new(4.0, -2.0));
+    assert_eq!(&a - &b, Vec2::new(-2.0, 6.0));
+    assert_eq!(&a * 2.0, Vec2::new(2.0, 4.0));
+    assert_eq!(&b / 2.0, Vec2::new(1.5, -2.0));
+    assert_eq!(a.dot(&b), -5.0);
+    assert_eq!(b.magnitude(), 5.0);
+
+    let mut n = b.clone();
+    n.normalize();
+    assert_eq!(n, Vec2::new(0.6, -0.8));
+}
+
+#[test]
+fn test_uv_interpolation() {
+    // UVs at the corners of a lightmap texel, interpolated across an edge
+    let uv0 = Vec2::new(0.0, 0.0);
+    let uv1 = Vec2::new(0.5, 1.0);
+
+    assert_eq!(Vec2::lerp(&uv0, &uv1, 0.0), uv0);
+    assert_eq!(Vec2::lerp(&uv0, &uv1, 1.0), uv1);
+    assert_eq!(Vec2::lerp(&uv0, &uv1, 0.5), Vec2::new(0.25, 0.5));
+
+    // Barycentric combination of three face UVs
+    let uv2 = Vec2::new(1.0, 0.0);
+    let p = &(&(&uv0 * 0.25) + &(&uv1 * 0.25)) + &(&uv2 * 0.5);
+    assert_eq!(p, Vec2::new(0.625, 0.25));
+
+    // Round trip through the face vertex UV fields
+    let mut vert = Vert::default();
+    vert.set_uv(&uv1);
+    assert_eq!(vert.u, 0.5);
+    assert_eq!(vert.v, 1.0);
+    assert_eq!(vert.uv(), uv1);
+}
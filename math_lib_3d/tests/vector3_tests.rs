@@ -40,3 +40,325 @@ fn test_mul_scalar_by_vector() {
     };
     assert_eq!(&r2, &expect2);
 }
+
+#[test]
+fn test_neg_vector() {
+    let zero = Vector3::zero();
+    assert_eq!(-&zero, Vector3::zero());
+
+    let v = Vector3 {
+        x: 1.0,
+        y: -2.0,
+        z: 3.0,
+    };
+    let expect = Vector3 {
+        x: -1.0,
+        y: 2.0,
+        z: -3.0,
+    };
+    assert_eq!(-&v, expect);
+    assert_eq!(-(-v), v);
+}
+
+#[test]
+fn test_div_assign_scalar() {
+    let mut v = Vector3::new(4.0, 6.0, 8.0);
+    v /= 2.0;
+    assert_eq!(v, Vector3::new(2.0, 3.0, 4.0));
+}
+
+#[test]
+fn test_normalized_matches_normalize() {
+    let v = Vector3::new(3.0, -4.0, 12.0);
+    let mut expect = v;
+    expect.normalize();
+
+    let n = v.normalized();
+    assert_eq!(n, expect);
+    assert!((n.magnitude() - 1.0).abs() < 0.0001);
+
+    // The original is left untouched
+    assert_eq!(v, Vector3::new(3.0, -4.0, 12.0));
+
+    // Zero vector comes back unchanged
+    assert_eq!(Vector3::zero().normalized(), Vector3::zero());
+}
+
+#[test]
+fn test_lerp() {
+    let a = Vector3::new(0.0, 2.0, -4.0);
+    let b = Vector3::new(4.0, 6.0, 4.0);
+
+    assert_eq!(Vector3::lerp(&a, &b, 0.0), a);
+    assert_eq!(Vector3::lerp(&a, &b, 1.0), b);
+    assert_eq!(Vector3::lerp(&a, &b, 0.5), Vector3::new(2.0, 4.0, 0.0));
+
+    // No clamping - t outside 0..1 extrapolates
+    assert_eq!(Vector3::lerp(&a, &b, 2.0), Vector3::new(8.0, 10.0, 12.0));
+}
+
+#[test]
+fn test_reflect() {
+    let v = Vector3::new(1.0, -1.0, 0.0);
+    let n = Vector3::new(0.0, 1.0, 0.0);
+    assert_eq!(v.reflect(&n), Vector3::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn test_project_and_reject() {
+    let v = Vector3::new(2.0, 2.0, 0.0);
+    let onto = Vector3::new(1.0, 0.0, 0.0);
+    assert_eq!(v.project_onto(&onto), Vector3::new(2.0, 0.0, 0.0));
+    assert_eq!(v.reject_from(&onto), Vector3::new(0.0, 2.0, 0.0));
+
+    // Projecting onto a zero vector gives the zero vector
+    assert_eq!(v.project_onto(&Vector3::zero()), Vector3::zero());
+}
+
+#[test]
+fn test_approx_eq() {
+    let a = Vector3::new(0.1, 0.2, 0.3);
+    let b = Vector3::new(0.1 + 0.000001, 0.2, 0.3 - 0.000001);
+    assert!(a.approx_eq(&b, DEFAULT_EPSILON));
+    assert!(!a.approx_eq(&Vector3::new(0.1, 0.2, 0.31), DEFAULT_EPSILON));
+
+    // Round trip through a lossy computation
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    let r = &(&v / 3.0) * 3.0;
+    assert!(v.approx_eq(&r, DEFAULT_EPSILON));
+}
+
+#[test]
+fn test_display() {
+    let v = Vector3::new(1.5, 2.0, -3.25);
+    assert_eq!(format!("{}", v), "(1.5, 2, -3.25)");
+    assert_eq!(format!("{:.2}", v), "(1.50, 2.00, -3.25)");
+}
+
+#[test]
+fn test_min_max_clamp() {
+    let a = Vector3::new(-1.0, 5.0, 0.0);
+    let b = Vector3::new(2.0, -3.0, 0.0);
+    assert_eq!(a.min(&b), Vector3::new(-1.0, -3.0, 0.0));
+    assert_eq!(a.max(&b), Vector3::new(2.0, 5.0, 0.0));
+
+    let lo = Vector3::new(-1.0, -1.0, -1.0);
+    let hi = Vector3::new(1.0, 1.0, 1.0);
+    let v = Vector3::new(-4.0, 0.5, 9.0);
+    assert_eq!(v.clamp(&lo, &hi), Vector3::new(-1.0, 0.5, 1.0));
+
+    // lo > hi on x: below lo clamps to lo, anything else to hi
+    let lo = Vector3::new(2.0, -1.0, -1.0);
+    let hi = Vector3::new(-2.0, 1.0, 1.0);
+    assert_eq!(Vector3::new(0.0, 0.0, 0.0).clamp(&lo, &hi).x, 2.0);
+    assert_eq!(Vector3::new(5.0, 0.0, 0.0).clamp(&lo, &hi).x, -2.0);
+}
+
+#[test]
+fn test_index() {
+    let mut v = Vector3::new(1.0, 2.0, 3.0);
+
+    let mut sum = 0.0;
+    for i in 0..3 {
+        sum += v[i];
+    }
+    assert_eq!(sum, 6.0);
+
+    v[1] = 5.0;
+    assert_eq!(v.y, 5.0);
+}
+
+#[test]
+#[should_panic(expected = "Vector3 index out of range")]
+fn test_index_out_of_range() {
+    let v = Vector3::zero();
+    let _ = v[3];
+}
+
+#[test]
+fn test_array_and_tuple_conversions() {
+    let a: [f32; 3] = Vector3::from([1.0, 2.0, 3.0]).into();
+    assert_eq!(a, [1.0, 2.0, 3.0]);
+
+    let t: (f32, f32, f32) = Vector3::from((1.0, 2.0, 3.0)).into();
+    assert_eq!(t, (1.0, 2.0, 3.0));
+
+    let v: Vector3 = [4.0, 5.0, 6.0].into();
+    assert_eq!(v, Vector3::new(4.0, 5.0, 6.0));
+}
+
+#[test]
+fn test_scalar_triple() {
+    let x = Vector3::new(1.0, 0.0, 0.0);
+    let y = Vector3::new(0.0, 1.0, 0.0);
+    let z = Vector3::new(0.0, 0.0, 1.0);
+
+    assert_eq!(scalar_triple(&x, &y, &z), 1.0);
+
+    // Swapping two arguments negates the result
+    assert_eq!(scalar_triple(&y, &x, &z), -1.0);
+    assert_eq!(scalar_triple(&x, &z, &y), -1.0);
+
+    let a = Vector3::new(2.0, 0.0, 0.0);
+    let b = Vector3::new(0.0, 3.0, 0.0);
+    let c = Vector3::new(0.0, 0.0, 4.0);
+    assert_eq!(box_volume(&a, &c, &b), 24.0);
+}
+
+#[test]
+fn test_rotate_about_axis() {
+    let v = Vector3::new(1.0, 0.0, 0.0);
+    let axis = Vector3::new(0.0, 0.0, 1.0);
+    let r = v.rotate_about_axis(&axis, std::f32::consts::FRAC_PI_2);
+    assert!(r.approx_eq(&Vector3::new(0.0, 1.0, 0.0), DEFAULT_EPSILON));
+
+    // Vectors along the axis are unchanged
+    let r = axis.rotate_about_axis(&axis, 1.0);
+    assert!(r.approx_eq(&axis, DEFAULT_EPSILON));
+}
+
+#[test]
+fn test_is_finite_and_has_nan() {
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert!(v.is_finite());
+    assert!(!v.has_nan());
+
+    let v = Vector3::new(1.0, f32::NAN, 3.0);
+    assert!(!v.is_finite());
+    assert!(v.has_nan());
+
+    let v = Vector3::new(f32::INFINITY, 2.0, f32::NEG_INFINITY);
+    assert!(!v.is_finite());
+    assert!(!v.has_nan());
+}
+
+#[test]
+fn test_cross_method() {
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(-4.0, 0.5, 2.0);
+    assert_eq!(a.cross(&b), cross_product(&a, &b));
+
+    let x = Vector3::new(1.0, 0.0, 0.0);
+    let y = Vector3::new(0.0, 1.0, 0.0);
+    assert_eq!(x.cross(&y), Vector3::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn test_distance_methods() {
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(4.0, 6.0, 3.0);
+    assert_eq!(a.distance_to(&b), distance(&a, &b));
+    assert_eq!(a.distance_squared_to(&b), distance_squared(&a, &b));
+    assert_eq!(a.distance_to(&b), 5.0);
+    assert_eq!(b.distance_squared_to(&a), 25.0);
+}
+
+#[test]
+fn test_spherical_round_trip() {
+    let directions = [
+        Vector3::new(0.0, 0.0, 1.0),
+        Vector3::new(3.0, 0.0, 0.0),
+        Vector3::new(-1.0, 2.0, -3.0),
+        Vector3::new(0.5, -4.0, 0.25),
+        Vector3::new(-2.0, -2.0, 7.0),
+    ];
+    for v in directions.iter() {
+        let (r, h, p) = v.to_spherical();
+        let back = Vector3::from_spherical(r, h, p);
+        assert!(back.approx_eq(v, 0.0001), "{} -> {}", v, back);
+    }
+
+    // +z is straight ahead, +x is heading 90 degrees, -y is pitched down
+    let (r, h, p) = Vector3::new(0.0, 0.0, 2.0).to_spherical();
+    assert_eq!((r, h, p), (2.0, 0.0, 0.0));
+    let (_, h, _) = Vector3::new(1.0, 0.0, 0.0).to_spherical();
+    assert!((h - std::f32::consts::FRAC_PI_2).abs() < DEFAULT_EPSILON);
+    let (_, _, p) = Vector3::new(0.0, -1.0, 1.0).to_spherical();
+    assert!((p - std::f32::consts::FRAC_PI_4).abs() < DEFAULT_EPSILON);
+}
+
+#[test]
+fn test_spherical_poles() {
+    assert_eq!(Vector3::zero().to_spherical(), (0.0, 0.0, 0.0));
+
+    let (r, h, p) = Vector3::new(0.0, 5.0, 0.0).to_spherical();
+    assert_eq!(r, 5.0);
+    assert_eq!(h, 0.0);
+    assert!((p + std::f32::consts::FRAC_PI_2).abs() < DEFAULT_EPSILON);
+
+    let (r, h, p) = Vector3::new(0.0, -5.0, 0.0).to_spherical();
+    assert_eq!(r, 5.0);
+    assert_eq!(h, 0.0);
+    assert!((p - std::f32::consts::FRAC_PI_2).abs() < DEFAULT_EPSILON);
+
+    let v = Vector3::from_spherical(5.0, 0.0, -std::f32::consts::FRAC_PI_2);
+    assert!(v.approx_eq(&Vector3::new(0.0, 5.0, 0.0), 0.0001));
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_add_sub_operand_forms() {
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(0.5, -1.0, 4.0);
+
+    let sum = Vector3::new(1.5, 1.0, 7.0);
+    assert_eq!(&a + &b, sum);
+    assert_eq!(a + &b, sum);
+    assert_eq!(&a + b, sum);
+    assert_eq!(a + b, sum);
+
+    let diff = Vector3::new(0.5, 3.0, -1.0);
+    assert_eq!(&a - &b, diff);
+    assert_eq!(a - &b, diff);
+    assert_eq!(&a - b, diff);
+    assert_eq!(a - b, diff);
+}
+
+#[test]
+fn test_vector3_is_copy() {
+    fn take(v: Vector3) -> f32 {
+        v.x + v.y + v.z
+    }
+
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert_eq!(take(v), 6.0);
+    assert_eq!(take(v), 6.0);
+
+    // Owned scalar and assign operators leave the source usable
+    let w = v * 2.0;
+    assert_eq!(w, Vector3::new(2.0, 4.0, 6.0));
+    assert_eq!(2.0 * v, w);
+    assert_eq!(w / 2.0, v);
+
+    let mut u = v;
+    u += w;
+    u -= v;
+    assert_eq!(u, w);
+}
+
+#[test]
+fn test_is_unit() {
+    assert!(Vector3::new(0.0, 1.0, 0.0).is_unit(DEFAULT_EPSILON));
+    assert!(Vector3::new(0.6, 0.0, 0.8).is_unit(0.0001));
+    assert!(!Vector3::new(1.0, 1.0, 0.0).is_unit(0.01));
+    assert!(!Vector3::zero().is_unit(0.01));
+}
+
+#[test]
+fn test_blend_normals() {
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let right = Vector3::new(1.0, 0.0, 0.0);
+
+    let n = blend_normals(&[up, right]);
+    let s = std::f32::consts::FRAC_1_SQRT_2;
+    assert!(n.approx_eq(&Vector3::new(s, s, 0.0), DEFAULT_EPSILON));
+    assert!(n.is_unit(0.0001));
+
+    // Opposing normals cancel, which must not produce NaN
+    let n = blend_normals(&[up, -up]);
+    assert_eq!(n, Vector3::zero());
+    assert!(!n.has_nan());
+
+    assert_eq!(blend_normals(&[]), Vector3::zero());
+    assert_eq!(blend_normals(&[right]), right);
+}
@@ -77,8 +77,7 @@ pub fn renderCube(config: &Config) {
 
         rv.p = cube.corner(i);
 //vl[i].argb = MAKE_ARGB(255, (i & 1) ? 255 : 0, (i & 2) ? 255 : 0, (i & 4) ? 255 : 0);
-        rv.n = rv.p;
-        rv.n.normalize();
+        rv.n = rv.p.normalized();
         rv.u = if i & 1 { 1.0 } else { 0.0 };
         rv.v = if i & 2 { 1.0 } else { 0.0 };
     }